@@ -129,7 +129,8 @@ impl AmberCompiler {
         let code = code.replace("\r\n", "\n").replace('\r', "\n");
         let cc = Compiler::new("Amber", rules::get_rules());
         let compiler = AmberCompiler { cc, path, options };
-        compiler.load_code(AmberCompiler::comment_shebang(code))
+        let code = rules::mark_text_literals(&AmberCompiler::comment_shebang(code));
+        compiler.load_code(code)
     }
 
     fn comment_shebang(code: String) -> String {
@@ -168,7 +169,8 @@ impl AmberCompiler {
                         format!("Unclosed {}", pos.data.as_ref().unwrap())
                     }
                 };
-                let code = self.cc.code.as_ref().expect(NO_CODE_PROVIDED).clone();
+                let code =
+                    rules::unmark_text_literals(self.cc.code.as_ref().expect(NO_CODE_PROVIDED));
                 let meta = ParserMetadata::new(vec![], self.path.clone(), Some(code));
                 Err(Message::new_err_at_position(&meta, pos).message(error_message))
             }
@@ -176,7 +178,7 @@ impl AmberCompiler {
     }
 
     pub fn parse(&self, tokens: Vec<Token>) -> Result<(Block, ParserMetadata), Message> {
-        let code = self.cc.code.as_ref().expect(NO_CODE_PROVIDED).clone();
+        let code = rules::unmark_text_literals(self.cc.code.as_ref().expect(NO_CODE_PROVIDED));
         let mut meta = ParserMetadata::new(tokens, self.path.clone(), Some(code));
         if let Err(Failure::Loud(err)) = check_all_blocks(&meta) {
             return Err(err);
@@ -264,6 +266,7 @@ impl AmberCompiler {
         &self,
         sudo_used: bool,
         shell_metadata_used: bool,
+        packed_used: bool,
         target_shell: &ShellType,
     ) -> FragmentKind {
         let mut preamble = Vec::new();
@@ -292,6 +295,11 @@ impl AmberCompiler {
                     .to_frag(),
             );
         }
+        if packed_used {
+            preamble.push(
+                RawFragment::new(include_str!("preambles/separators.sh").trim_end()).to_frag(),
+            );
+        }
         BlockFragment::new(preamble, false).to_frag()
     }
 
@@ -299,6 +307,15 @@ impl AmberCompiler {
         let sudo_used = meta.sudo_used;
         let shellname_used = meta.shellname_used;
         let shellversion_used = meta.shellversion_used;
+        let packed_used = meta.packed_used;
+        // Associative arrays were introduced in Bash 4.0
+        if let Some(pos) = meta.map_used.clone() {
+            if Self::resolve_target_shell(self.options.target).is_bash_legacy() {
+                return Err(Message::new_err_at_position(&meta, pos)
+                    .message("Maps are not supported by the 'bash-3.2' target")
+                    .comment("Use a different target shell or replace the map with arrays"));
+            }
+        }
         let ast_forest = self.get_sorted_ast_forest(block, &meta);
         let mut meta_translate = TranslateMetadata::new(meta, &self.options);
         let time = Instant::now();
@@ -307,6 +324,7 @@ impl AmberCompiler {
         result.append(self.gen_preamble(
             sudo_used,
             shellname_used || shellversion_used,
+            packed_used,
             &meta_translate.target.shell,
         ));
 
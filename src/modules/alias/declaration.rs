@@ -0,0 +1,132 @@
+use crate::modules::function::declaration_utils::is_declaration_comment_doc;
+use crate::modules::prelude::*;
+use crate::modules::statement::comment_doc::CommentDoc;
+use crate::modules::types::{is_type_name, parse_type, resolve_type, NominalType, Type};
+use crate::modules::variable::variable_name_extensions;
+use crate::utils::context::TypeDecl;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "type"]
+#[kind = "stmt"]
+pub struct AliasDeclaration {
+    pub name: String,
+    pub kind: Type,
+    /// Distinct types are not interchangeable with the type they wrap
+    pub is_distinct: bool,
+    pub is_public: bool,
+    pub comment: Option<CommentDoc>,
+    /// Token for type name (for error positioning)
+    pub name_token: Option<Token>,
+    pub type_token: Option<Token>,
+}
+
+impl AliasDeclaration {
+    fn render_signature(&self) -> String {
+        let public = if self.is_public { "pub " } else { "" };
+        let assign = if self.is_distinct { "" } else { "= " };
+        format!("{public}type {} {assign}{}", self.name, self.kind)
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for AliasDeclaration {
+    syntax_name!("Type Alias Declaration");
+
+    fn new() -> Self {
+        AliasDeclaration {
+            name: String::new(),
+            kind: Type::Null,
+            is_distinct: false,
+            is_public: false,
+            comment: None,
+            name_token: None,
+            type_token: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Parse the type comment
+        if is_declaration_comment_doc(meta, "type") {
+            let mut comment = CommentDoc::new();
+            syntax(meta, &mut comment)?;
+            self.comment = Some(comment);
+        }
+        self.is_public = token(meta, "pub").is_ok();
+        token(meta, "type")?;
+        self.name_token = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        // Without the assignment a new distinct type is declared
+        self.is_distinct = token(meta, "=").is_err();
+        self.type_token = meta.get_current_token();
+        self.kind = parse_type(meta)?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for AliasDeclaration {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if !meta.is_global_scope() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                "Types can only be declared in the global scope"
+            );
+        }
+        if !is_type_name(&self.name) {
+            return error!(meta, self.name_token.clone() => {
+                message: format!("Type name '{}' is not in pascal case", self.name),
+                comment: "Type names have to start with an uppercase letter, for example 'Port'"
+            });
+        }
+        if meta.get_type_declaration(&self.name).is_some() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                format!("Type '{}' is already defined", self.name)
+            );
+        }
+
+        let kind = resolve_type(meta, &self.kind, self.type_token.clone())?;
+        let kind = if self.is_distinct {
+            if !matches!(kind, Type::Text | Type::Int | Type::Num | Type::Bool) {
+                return error!(meta, self.type_token.clone() => {
+                    message: format!("Type '{}' cannot wrap type '{kind}'", self.name),
+                    comment: "Distinct types can only wrap 'Text', 'Int', 'Num' or 'Bool'"
+                });
+            }
+            Type::Nominal(NominalType {
+                name: self.name.clone(),
+                inner: Box::new(kind),
+            })
+        } else {
+            kind
+        };
+        meta.add_type_declaration(TypeDecl {
+            name: self.name.clone(),
+            kind,
+            is_public: self.is_public,
+        });
+        Ok(())
+    }
+}
+
+impl TranslateModule for AliasDeclaration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        FragmentKind::Empty
+    }
+}
+
+impl DocumentationModule for AliasDeclaration {
+    fn document(&self, meta: &ParserMetadata) -> String {
+        let mut result = vec![];
+        result.push(format!("## `{}`\n", self.name));
+        result.push("```ab".to_string());
+        result.push(self.render_signature());
+        result.push("```\n".to_string());
+        if let Some(comment) = &self.comment {
+            result.push(comment.document(meta));
+        }
+        result.push("".to_string());
+        result.join("\n")
+    }
+}
@@ -44,11 +44,12 @@ impl TypeCheckModule for Disown {
 
             let pids_type = pids.get_type();
             let expected_array_type = Type::array_of(Type::Int);
-            if pids_type != Type::Int && !pids_type.is_allowed_in(&expected_array_type) {
+            let is_job = pids_type == Type::job() || pids_type == Type::array_of(Type::job());
+            if pids_type != Type::Int && !is_job && !pids_type.is_allowed_in(&expected_array_type) {
                 let position = pids.get_position();
                 return error_pos!(meta, position => {
-                    message: "Builtin function `disown` can only be used with values of type Int or [Int]",
-                    comment: format!("Given type: {}, expected type: {} or {}", pids_type, Type::Int, expected_array_type)
+                    message: "Builtin function `disown` can only be used with values of type Int, Job or arrays of them",
+                    comment: format!("Given type: {}, expected type: {}, {}, {} or {}", pids_type, Type::Int, Type::job(), expected_array_type, Type::array_of(Type::job()))
                 });
             }
         }
@@ -0,0 +1,58 @@
+use crate::fragments;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::modules::variable::get::VariableGet;
+use crate::translate::job::translate_job_kill;
+use crate::utils::ParserMetadata;
+use heraclitus_compiler::prelude::*;
+use heraclitus_compiler::syntax_name;
+
+#[derive(Debug, Clone)]
+pub struct JobKill {
+    job: VariableGet,
+    tok: Option<Token>,
+}
+
+impl SyntaxModule<ParserMetadata> for JobKill {
+    syntax_name!("JobKill");
+
+    fn new() -> Self {
+        JobKill {
+            job: VariableGet::new(),
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        syntax(meta, &mut self.job)?;
+        token(meta, ".")?;
+        token(meta, "kill")?;
+        token(meta, "(")?;
+        token(meta, ")")?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for JobKill {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.job.typecheck(meta)?;
+        let job_type = self.job.get_type();
+        if job_type != Type::job() {
+            return error!(meta, self.tok.clone() => {
+                message: "Method `kill` can only be called on values of type Job",
+                comment: format!("Given type: {job_type}")
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for JobKill {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let job = self.job.translate(meta);
+        fragments!(translate_job_kill(job), " || true")
+    }
+}
+
+crate::impl_documentation_noop!(JobKill);
@@ -4,6 +4,7 @@ use crate::modules::expression::unop::UnOp;
 use crate::modules::prelude::*;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::{Type, Typed};
+use crate::translate::map::get_map_variable;
 use crate::translate::module::TranslateModule;
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use amber_meta::AutoKeyword;
@@ -52,12 +53,16 @@ impl TypeCheckModule for Len {
         // Typecheck the expression first
         self.value.typecheck(meta)?;
 
-        let accepted_types = Type::Union(vec![Type::Text, Type::array_of(Type::Generic)]);
+        let accepted_types = Type::Union(vec![
+            Type::Text,
+            Type::array_of(Type::Generic),
+            Type::map_of(Type::Generic),
+        ]);
         if !self.value.get_type().is_allowed_in(&accepted_types) {
             let msg = self
                 .value
                 .get_error_message(meta)
-                .message("Length can only be applied to text or array types");
+                .message("Length can only be applied to text, array or map types");
             return Err(Failure::Loud(msg));
         }
         Ok(())
@@ -67,6 +72,11 @@ impl TypeCheckModule for Len {
 impl TranslateModule for Len {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let value = self.value.translate(meta);
+        if self.value.get_type().is_map() {
+            return get_map_variable(meta, value, &self.value.get_type())
+                .with_length_getter(true)
+                .to_frag();
+        }
         let id = meta.gen_value_id();
         let var_stmt =
             VarStmtFragment::new("__length", self.value.get_type(), value).with_global_id(id);
@@ -4,6 +4,7 @@ pub mod cp;
 pub mod disown;
 pub mod echo;
 pub mod exit;
+pub mod kill;
 pub mod len;
 pub mod lines;
 pub mod lock;
@@ -17,4 +18,5 @@ pub mod shellname;
 pub mod shellversion;
 pub mod sleep;
 pub mod touch;
+pub mod unset;
 pub mod wait;
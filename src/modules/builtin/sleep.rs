@@ -2,6 +2,7 @@ use crate::fragments;
 use crate::modules::command::modifier::CommandModifier;
 use crate::modules::condition::failure_handler::FailureHandler;
 use crate::modules::expression::expr::Expr;
+use crate::modules::expression::literal::duration::translate_duration_seconds;
 
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
@@ -60,11 +61,11 @@ impl TypeCheckModule for Sleep {
         self.modifier.use_modifiers(meta, |_, meta| {
             self.value.typecheck(meta)?;
             let time_type = self.value.get_type();
-            if time_type != Type::Int && time_type != Type::Num {
+            if ![Type::Int, Type::Num, Type::duration()].contains(&time_type) {
                 let position = self.value.get_position();
                 return error_pos!(meta, position => {
-                    message: "Builtin function `sleep` can only be used with values of type Int or Num",
-                    comment: format!("Given type: {}, expected type: {} or {}", time_type, Type::Int, Type::Num)
+                    message: "Builtin function `sleep` can only be used with values of type Int, Num or Duration",
+                    comment: format!("Given type: {}, expected type: {}, {} or {}", time_type, Type::Int, Type::Num, Type::duration())
                 });
             }
             self.failure_handler.typecheck(meta)?;
@@ -91,25 +92,32 @@ impl TranslateModule for Sleep {
             meta.gen_suppress().to_frag()
         });
 
+        let is_duration = self.value.get_type() == Type::duration();
         let check = match self.value.get_type() {
-            Type::Int => ArithmeticFragment::new(
-                var_expr.clone().with_quotes(false).to_frag(),
-                ArithOp::Ge,
-                raw_fragment!("0"),
-            )
-            .to_frag(),
-            _ => translate_float_computation(
+            Type::Num => translate_float_computation(
                 meta,
                 ArithOp::Ge,
                 Some(var_expr.clone().to_frag()),
                 Some(raw_fragment!("0")),
             ),
+            _ => ArithmeticFragment::new(
+                var_expr.clone().with_quotes(false).to_frag(),
+                ArithOp::Ge,
+                raw_fragment!("0"),
+            )
+            .to_frag(),
         };
 
+        // Duration is stored in milliseconds while `sleep` expects seconds
+        let seconds = if is_duration {
+            translate_duration_seconds(var_expr.clone().with_quotes(false).to_frag())
+        } else {
+            var_expr.to_frag()
+        };
         let sleep_cmd = ListFragment::new(
             vec![
                 raw_fragment!("sleep"),
-                var_expr.to_frag(),
+                seconds,
                 silent.clone(),
                 suppress.clone()
             ]
@@ -0,0 +1,82 @@
+use crate::fragments;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::modules::variable::{
+    handle_variable_reference, prevent_constant_mutation, prevent_job_mutation,
+    variable_name_extensions,
+};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "unset"]
+#[kind = "builtin_stmt"]
+pub struct Unset {
+    name: String,
+    tok: Option<Token>,
+    global_id: Option<usize>,
+    key: Expr,
+}
+
+impl SyntaxModule<ParserMetadata> for Unset {
+    syntax_name!("Unset");
+
+    fn new() -> Self {
+        Unset {
+            name: String::new(),
+            tok: None,
+            global_id: None,
+            key: Expr::new(),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "unset")?;
+        token(meta, "(")?;
+        self.tok = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        token(meta, ",")?;
+        syntax(meta, &mut self.key)?;
+        token(meta, ")")?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Unset {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.key.typecheck(meta)?;
+        let variable = handle_variable_reference(meta, &self.tok, &self.name)?;
+        if !variable.kind.is_map() {
+            return error!(meta, self.tok.clone() => {
+                message: "Builtin function `unset` can only be used with maps",
+                comment: format!("Given type: {}", variable.kind)
+            });
+        }
+        prevent_constant_mutation(meta, &self.tok, &self.name, variable.is_const)?;
+        prevent_job_mutation(meta, &self.tok, &self.name)?;
+        meta.mark_var_modified(&self.name);
+        self.global_id = variable.global_id;
+
+        if self.key.get_type() != Type::Text {
+            let pos = self.key.get_position();
+            return error_pos!(meta, pos => {
+                message: "Map keys have to be of type 'Text'",
+                comment: format!("Given key is of type '{}'", self.key.get_type())
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for Unset {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let name = VarExprFragment::new(&self.name, Type::Text)
+            .with_global_id(self.global_id)
+            .with_render_type(VarRenderType::NameOf)
+            .to_frag();
+        let key = self.key.translate(meta).with_quotes(false);
+        fragments!("unset \"", name, "[", key, "]\"")
+    }
+}
+
+crate::impl_documentation_noop!(Unset);
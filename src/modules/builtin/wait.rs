@@ -1,4 +1,5 @@
-use crate::fragments;
+use crate::translate::fragments::get_variable_name;
+use crate::{fragments, raw_fragment};
 use crate::modules::command::modifier::CommandModifier;
 use crate::modules::condition::failure_handler::FailureHandler;
 use crate::modules::expression::expr::Expr;
@@ -33,9 +34,13 @@ impl SyntaxModule<ParserMetadata> for Await {
         syntax(meta, &mut self.modifier)?;
         self.modifier.use_modifiers(meta, |_, meta| {
             token(meta, "await")?;
-            token(meta, "(")?;
-            syntax(meta, &mut self.pids)?;
-            token(meta, ")")?;
+            // Jobs can be awaited without the parentheses
+            if token(meta, "(").is_ok() {
+                syntax(meta, &mut self.pids)?;
+                token(meta, ")")?;
+            } else {
+                syntax(meta, &mut self.pids)?;
+            }
 
             if let Err(e) = syntax(meta, &mut self.failure_handler) {
                 match e {
@@ -58,11 +63,12 @@ impl TypeCheckModule for Await {
         self.modifier.use_modifiers(meta, |_, meta| {
             self.pids.typecheck(meta)?;
             let pids_type = self.pids.get_type();
-            if pids_type != Type::array_of(Type::Int) && pids_type != Type::Int {
+            let allowed = [Type::Int, Type::array_of(Type::Int), Type::job(), Type::array_of(Type::job())];
+            if !allowed.contains(&pids_type) {
                 let position = self.pids.get_position();
                 return error_pos!(meta, position => {
-                    message: "Builtin function `await` can only be used with values of type Int or [Int]",
-                    comment: format!("Given type: {}, expected type: {} or {}", pids_type, Type::Int, Type::array_of(Type::Int))
+                    message: "Builtin function `await` can only be used with values of type Int, Job or arrays of them",
+                    comment: format!("Given type: {}, expected type: {}, {}, {} or {}", pids_type, Type::Int, Type::job(), Type::array_of(Type::Int), Type::array_of(Type::job()))
                 });
             }
             self.failure_handler.typecheck(meta)?;
@@ -74,7 +80,6 @@ impl TypeCheckModule for Await {
 impl TranslateModule for Await {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let pids = self.pids.translate(meta);
-        let handler = self.failure_handler.translate(meta);
         let sudo_prefix = meta.with_sudoed(self.modifier.is_sudo || meta.sudoed, |meta| {
             meta.gen_sudo_prefix().to_frag()
         });
@@ -84,12 +89,45 @@ impl TranslateModule for Await {
         let suppress = meta.with_suppress(self.modifier.is_suppress || meta.suppress, |meta| {
             meta.gen_suppress().to_frag()
         });
+        let id = meta.gen_value_id();
+        let status = VarStmtFragment::new("__await_status", Type::Int, raw_fragment!("0"))
+            .with_global_id(id)
+            .with_local(meta.fun_meta.is_some())
+            .with_optimization_when_unused(false);
+        let status_name = status.get_name();
+        let status_expr = VarExprFragment::from_stmt(&status).to_frag();
+        let pid = get_variable_name("__await_pid", Some(id));
+        let wait = ListFragment::new(vec![
+            sudo_prefix,
+            raw_fragment!("wait \"${{{pid}}}\""),
+            suppress,
+            silent,
+        ])
+        .with_spaces()
+        .to_frag();
+        // A trapped signal interrupts `wait` while the job keeps running
+        let retry = raw_fragment!(
+            "[ \"${{{status_name}}}\" -gt 128 ] && kill -0 \"${{{pid}}}\" 2>/dev/null || break"
+        );
         BlockFragment::new(
             vec![
-                ListFragment::new(vec![sudo_prefix, fragments!("wait"), pids, suppress, silent])
-                    .with_spaces()
-                    .to_frag(),
-                handler,
+                status.to_frag(),
+                fragments!(raw_fragment!("for {pid} in "), pids, "; do"),
+                BlockFragment::new(
+                    vec![
+                        fragments!("while :; do"),
+                        BlockFragment::new(
+                            vec![wait, raw_fragment!("{status_name}=$?"), retry],
+                            true,
+                        )
+                        .to_frag(),
+                        fragments!("done"),
+                    ],
+                    true,
+                )
+                .to_frag(),
+                fragments!("done"),
+                self.failure_handler.translate_status(meta, status_expr),
             ],
             false,
         )
@@ -98,3 +136,38 @@ impl TranslateModule for Await {
 }
 
 crate::impl_documentation_noop!(Await);
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "await_all"]
+#[kind = "builtin_stmt"]
+pub struct AwaitAll;
+
+impl SyntaxModule<ParserMetadata> for AwaitAll {
+    syntax_name!("AwaitAll");
+
+    fn new() -> Self {
+        AwaitAll
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "await_all")?;
+        token(meta, "(")?;
+        token(meta, ")")?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for AwaitAll {
+    fn typecheck(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TranslateModule for AwaitAll {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        // Waits for all background jobs regardless of their status codes
+        fragments!("wait")
+    }
+}
+
+crate::impl_documentation_noop!(AwaitAll);
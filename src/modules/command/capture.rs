@@ -0,0 +1,124 @@
+use super::cmd::{Command, RedirectKind};
+use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::modules::variable::get::VariableGet;
+use crate::translate::packed::{pack_value, pack_values};
+use crate::utils::context::{Context, VariableDecl};
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Temporary file that receives the error output of the captured command
+const CAPTURE_FILE: &str = "__capture_file";
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "capture"]
+pub struct Capture {
+    command: Box<Command>,
+    file_global_id: Option<usize>,
+}
+
+impl Typed for Capture {
+    fn get_type(&self) -> Type {
+        Type::Tuple(vec![Type::Text, Type::Text, Type::Int])
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Capture {
+    syntax_name!("Capture");
+
+    fn new() -> Self {
+        Capture {
+            command: Box::new(Command::new()),
+            file_global_id: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "capture")?;
+        // The status code is a part of the result thus the failure does not have to be handled
+        meta.with_context_fn(Context::set_is_trust_ctx, true, |meta| {
+            syntax(meta, &mut *self.command)
+        })?;
+        if self.command.has_redirect(RedirectKind::Error) {
+            return error!(meta, tok => {
+                message: "Error output of a captured command cannot be redirected",
+                comment: "The error output is returned as the second value of the capture"
+            });
+        }
+        if self.command.ends_with_function() {
+            return error!(meta, tok => {
+                message: "Captured pipeline must end with a command",
+                comment: "Call the function on the output of the capture instead"
+            });
+        }
+        let file = Expr {
+            value: Some(ExprType::VariableGet(VariableGet::from_name(
+                CAPTURE_FILE,
+                tok.clone(),
+            ))),
+            kind: Type::Text,
+            position: Some(PositionInfo::from_token(meta, tok.clone())),
+        };
+        self.command.redirect(RedirectKind::Error, file, tok);
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Capture {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        meta.with_push_scope(true, |meta| {
+            self.file_global_id =
+                meta.add_var(VariableDecl::new(CAPTURE_FILE.to_string(), Type::Text));
+            self.command.typecheck(meta)
+        })?;
+        meta.packed_used = true;
+        Ok(())
+    }
+}
+
+impl TranslateModule for Capture {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let is_local = meta.fun_meta.is_some();
+        let file = VarStmtFragment::new(
+            CAPTURE_FILE,
+            Type::Text,
+            SubprocessFragment::new(raw_fragment!("mktemp")).to_frag(),
+        )
+        .with_global_id(self.file_global_id)
+        .with_local(is_local)
+        .with_optimization_when_unused(false);
+        let file_expr = VarExprFragment::from_stmt(&file).to_frag();
+        meta.stmt_queue.push_back(file.to_frag());
+
+        let output = meta.with_expr_ctx(true, |meta| self.command.translate(meta));
+        // The status code is stored by the failure handler of the command
+        let status = VarExprFragment::new("__status", Type::Int).to_frag();
+        let status = VarStmtFragment::new("__capture_status", Type::Int, status)
+            .with_global_id(meta.gen_value_id())
+            .with_local(is_local);
+        let status_expr = VarExprFragment::from_stmt(&status).to_frag();
+        let error = VarStmtFragment::new(
+            "__capture_error",
+            Type::Text,
+            SubprocessFragment::new(fragments!("cat ", file_expr.clone())).to_frag(),
+        )
+        .with_global_id(meta.gen_value_id())
+        .with_local(is_local);
+        let error_expr = VarExprFragment::from_stmt(&error).to_frag();
+        meta.stmt_queue.push_back(status.to_frag());
+        meta.stmt_queue.push_back(error.to_frag());
+        meta.stmt_queue.push_back(fragments!("rm -f ", file_expr));
+
+        let values = vec![
+            pack_value(meta, output, &Type::Text),
+            pack_value(meta, error_expr, &Type::Text),
+            pack_value(meta, status_expr, &Type::Int),
+        ];
+        pack_values(values, self.get_type().packed_depth())
+    }
+}
+
+crate::impl_documentation_noop!(Capture);
@@ -1,23 +1,474 @@
 use super::modifier::CommandModifier;
 use crate::modules::condition::failure_handler::FailureHandler;
+use crate::modules::expression::expr::{Expr, ExprType};
 use crate::modules::expression::interpolated_region::{
     parse_interpolated_region, InterpolatedRegionType,
 };
 use crate::modules::expression::literal::text::TextPart;
+use crate::modules::function::invocation::FunctionInvocation;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::modules::variable::get::VariableGet;
+use crate::modules::variable::variable_name_extensions;
+use crate::utils::context::VariableDecl;
+use crate::{fragments, raw_fragment};
 use heraclitus_compiler::prelude::*;
 
+/// Output of the previous stage that is passed to a function of the pipeline
+const PIPE_INPUT: &str = "__pipe_input";
+/// Status code of the last failed stage of the pipeline
+const PIPE_STATUS: &str = "__pipe_status";
+
+/// Stage of a pipeline that receives the output of the previous stage
+#[derive(Debug, Clone)]
+pub enum PipeStage {
+    /// Command that reads the output from its standard input
+    Command(Vec<TextPart>),
+    /// Function that is called with the output as its only argument
+    Function {
+        name: String,
+        tok: Option<Token>,
+        invocation: Box<FunctionInvocation>,
+        input_global_id: Option<usize>,
+    },
+}
+
+/// Standard stream of the command that is redirected
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedirectKind {
+    /// `> path` writes the output to a file
+    Output,
+    /// `>> path` appends the output to a file
+    Append,
+    /// `2> path` writes the error output to a file
+    Error,
+    /// `< value` feeds the text to the input of the command
+    Input,
+}
+
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    kind: RedirectKind,
+    value: Expr,
+    tok: Option<Token>,
+}
+
+impl Redirect {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let operator = match self.kind {
+            RedirectKind::Output => ">",
+            RedirectKind::Append => ">>",
+            RedirectKind::Error => "2>",
+            RedirectKind::Input => "<<<",
+        };
+        fragments!(raw_fragment!("{operator} "), self.value.translate(meta))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Command {
     parts: Vec<TextPart>,
+    stages: Vec<PipeStage>,
+    redirects: Vec<Redirect>,
     modifier: CommandModifier,
     failure_handler: FailureHandler,
 }
 
 impl Typed for Command {
     fn get_type(&self) -> Type {
-        Type::Text
+        match self.stages.last() {
+            Some(PipeStage::Function { invocation, .. }) => invocation.get_type(),
+            _ => Type::Text,
+        }
+    }
+}
+
+impl Command {
+    /// Whether the failure of this command is left to the operator that follows it
+    pub fn can_fail(&self) -> bool {
+        self.failure_handler.is_implicit
+    }
+
+    /// Whether the stream of given kind is redirected
+    pub fn has_redirect(&self, kind: RedirectKind) -> bool {
+        self.redirects.iter().any(|redirect| redirect.kind == kind)
+    }
+
+    /// Whether the output of the pipeline is passed to a function
+    pub fn ends_with_function(&self) -> bool {
+        matches!(self.stages.last(), Some(PipeStage::Function { .. }))
+    }
+
+    /// Whether the output of any stage of the pipeline is passed to a function
+    pub fn has_function_stage(&self) -> bool {
+        self.stages
+            .iter()
+            .any(|stage| matches!(stage, PipeStage::Function { .. }))
+    }
+
+    /// Whether the failure of the command is handled by the handler written after it
+    pub fn has_failure_handler(&self) -> bool {
+        self.failure_handler.is_explicit()
+    }
+
+    /// Redirects the stream of given kind to the value of the expression
+    pub fn redirect(&mut self, kind: RedirectKind, value: Expr, tok: Option<Token>) {
+        self.redirects.push(Redirect { kind, value, tok });
+    }
+
+    fn parse_stage(meta: &mut ParserMetadata) -> Result<PipeStage, Failure> {
+        let tok = meta.get_current_token();
+        if tok.as_ref().is_some_and(|tok| tok.word.starts_with('$')) {
+            let parts = parse_interpolated_region(meta, &InterpolatedRegionType::Command)?;
+            return Ok(PipeStage::Command(parts));
+        }
+        let Ok(name) = variable(meta, variable_name_extensions()) else {
+            return error!(meta, tok => {
+                message: "Expected a command or a function name after '|'",
+                comment: "Output of a command can be piped into another command or a function that takes a single Text argument"
+            });
+        };
+        let input = Expr {
+            value: Some(ExprType::VariableGet(VariableGet::from_name(
+                PIPE_INPUT,
+                tok.clone(),
+            ))),
+            kind: Type::Text,
+            position: Some(PositionInfo::from_token(meta, tok.clone())),
+        };
+        Ok(PipeStage::Function {
+            invocation: Box::new(FunctionInvocation::with_argument(&name, tok.clone(), input)),
+            name,
+            tok,
+            input_global_id: None,
+        })
+    }
+
+    /// Redirections take precedence over comparisons, so the result of a command
+    /// has to be put in parentheses to be compared with `<` or `>`
+    fn parse_redirect(meta: &mut ParserMetadata) -> Result<Option<Redirect>, Failure> {
+        let tok = meta.get_current_token();
+        let index = meta.get_index();
+        let kind = if token(meta, "<").is_ok() {
+            RedirectKind::Input
+        } else if token(meta, ">").is_ok() {
+            if token(meta, ">").is_ok() {
+                RedirectKind::Append
+            } else {
+                RedirectKind::Output
+            }
+        } else if token(meta, "2").is_ok() && token(meta, ">").is_ok() {
+            RedirectKind::Error
+        } else {
+            meta.set_index(index);
+            return Ok(None);
+        };
+        let mut value = Expr::new();
+        syntax(meta, &mut value)?;
+        Ok(Some(Redirect { kind, value, tok }))
+    }
+
+    fn parse_redirects(
+        redirects: &mut Vec<Redirect>,
+        stages: &[PipeStage],
+        meta: &mut ParserMetadata,
+    ) -> SyntaxResult {
+        while let Some(redirect) = Self::parse_redirect(meta)? {
+            let tok = redirect.tok.clone();
+            if redirect.kind == RedirectKind::Input {
+                if redirects
+                    .iter()
+                    .any(|redirect| redirect.kind == RedirectKind::Input)
+                {
+                    return error!(meta, tok, "Input of the command can only be given once");
+                }
+            } else if let Some(PipeStage::Function { name, .. }) = stages.last() {
+                return error!(meta, tok => {
+                    message: "Output of the pipeline cannot be redirected",
+                    comment: format!("The pipeline ends with function '{name}' that returns its output as a value")
+                });
+            }
+            redirects.push(redirect);
+        }
+        Ok(())
+    }
+
+    fn typecheck_redirects(redirects: &mut [Redirect], meta: &mut ParserMetadata) -> SyntaxResult {
+        for redirect in redirects.iter_mut() {
+            redirect.value.typecheck(meta)?;
+            let kind = redirect.value.get_type();
+            if kind != Type::Text {
+                let pos = redirect.value.get_position();
+                let message = match redirect.kind {
+                    RedirectKind::Input => "Input of the command must be of type Text",
+                    _ => "Path of the redirection must be of type Text",
+                };
+                return error_pos!(meta, pos => {
+                    message: message,
+                    comment: format!("Given type: {kind}")
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Translates the input and the output redirections of the command
+    fn translate_redirects(
+        &self,
+        meta: &mut TranslateMetadata,
+    ) -> (FragmentKind, Vec<FragmentKind>) {
+        let mut input = FragmentKind::Empty;
+        let mut outputs = vec![];
+        for redirect in &self.redirects {
+            match redirect.kind {
+                RedirectKind::Input => input = redirect.translate(meta),
+                _ => outputs.push(redirect.translate(meta)),
+            }
+        }
+        (input, outputs)
+    }
+
+    fn typecheck_stages(stages: &mut [PipeStage], meta: &mut ParserMetadata) -> SyntaxResult {
+        let count = stages.len();
+        for (index, stage) in stages.iter_mut().enumerate() {
+            match stage {
+                PipeStage::Command(parts) => {
+                    for part in parts.iter_mut() {
+                        part.typecheck(meta)?;
+                    }
+                }
+                PipeStage::Function {
+                    name,
+                    tok,
+                    invocation,
+                    input_global_id,
+                } => {
+                    if meta
+                        .get_fun_declaration(name)
+                        .is_some_and(|fun| fun.is_failable)
+                    {
+                        return error!(meta, tok.clone() => {
+                            message: format!("Failable function '{name}' cannot be used in a pipeline"),
+                            comment: "Call the function separately to handle its failure"
+                        });
+                    }
+                    // The output is passed through a variable that only the call can see
+                    meta.with_push_scope(true, |meta| {
+                        *input_global_id =
+                            meta.add_var(VariableDecl::new(PIPE_INPUT.to_string(), Type::Text));
+                        invocation.typecheck(meta)
+                    })?;
+                    if index + 1 < count && invocation.get_type() != Type::Text {
+                        return error!(meta, tok.clone() => {
+                            message: format!("Function '{name}' cannot pass its output to the next stage of the pipeline"),
+                            comment: format!("Only functions that return Text can be followed by another stage, given type: {}", invocation.get_type())
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Translates a command of the pipeline with its sudo prefix and suppressed error output
+    fn translate_command(parts: &[TextPart], meta: &mut TranslateMetadata) -> FragmentKind {
+        let parts = TextPart::to_interpolable_parts(parts, meta);
+        let command =
+            InterpolableFragment::new(parts, InterpolableRenderType::GlobalContext).to_frag();
+        let sudo_prefix = meta.gen_sudo_prefix();
+        let suppress = meta.gen_suppress();
+        ListFragment::new(vec![sudo_prefix, command, suppress])
+            .with_spaces()
+            .to_frag()
+    }
+
+    /// Joins the commands into a pipeline that fails when any of its commands fails
+    fn translate_pipeline(
+        meta: &TranslateMetadata,
+        input: Option<FragmentKind>,
+        commands: Vec<FragmentKind>,
+        outputs: Vec<FragmentKind>,
+    ) -> FragmentKind {
+        let mut pipeline = vec![];
+        // Redirections of a group apply to all of its commands
+        let is_grouped = !outputs.is_empty();
+        if is_grouped {
+            pipeline.push(raw_fragment!("{{ "));
+        }
+        if input.is_some() || commands.len() > 1 {
+            pipeline.push(raw_fragment!("set -o pipefail; "));
+        }
+        if let Some(input) = input {
+            pipeline.push(fragments!("printf '%s\\n' ", input, " | "));
+        }
+        for (index, command) in commands.into_iter().enumerate() {
+            if index > 0 {
+                pipeline.push(raw_fragment!(" | "));
+            }
+            pipeline.push(command);
+        }
+        if is_grouped {
+            pipeline.push(raw_fragment!("; }}"));
+        }
+        pipeline.push(meta.gen_silent());
+        // Redirections come last so that they take precedence over the silenced output
+        for output in outputs {
+            pipeline.push(fragments!(" ", output));
+        }
+        ListFragment::new(pipeline).to_frag()
+    }
+
+    /// Runs the translation with the modifiers of the command applied
+    fn with_modifiers<T>(
+        &self,
+        meta: &mut TranslateMetadata,
+        mut body: impl FnMut(&mut TranslateMetadata) -> T,
+    ) -> T {
+        let is_silenced = self.modifier.is_silent || meta.silenced;
+        let is_suppress = self.modifier.is_suppress || meta.suppress;
+        let is_sudoed = self.modifier.is_sudo || meta.sudoed;
+        meta.with_silenced(is_silenced, |meta| {
+            meta.with_suppress(is_suppress, |meta| meta.with_sudoed(is_sudoed, &mut body))
+        })
+    }
+
+    /// Translates the command literal with its redirections without handling its failure
+    fn translate_single(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let parts = TextPart::to_interpolable_parts(&self.parts, meta);
+        let command =
+            InterpolableFragment::new(parts, InterpolableRenderType::GlobalContext).to_frag();
+        let silent = meta.gen_silent().to_frag();
+        let suppress = meta.gen_suppress().to_frag();
+        let sudo_prefix = meta.gen_sudo_prefix().to_frag();
+        let (input, outputs) = self.translate_redirects(meta);
+        let translation = ListFragment::new(vec![sudo_prefix, command, input])
+            .with_spaces()
+            .to_frag();
+        let translation = if outputs.is_empty() {
+            ListFragment::new(vec![translation, suppress, silent])
+        } else {
+            // Redirections of a group apply to all commands of the command literal
+            // and come last so that they take precedence over the silenced output
+            let mut group = vec![fragments!("{ ", translation, "; }"), suppress, silent];
+            group.extend(outputs);
+            ListFragment::new(group)
+        };
+        translation.with_spaces().to_frag()
+    }
+
+    /// Translates the command without handling its failure so that it can run as a background job
+    pub fn translate_job(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let command = self.with_modifiers(meta, |meta| {
+            if self.stages.is_empty() {
+                return self.translate_single(meta);
+            }
+            let (stdin, outputs) = self.translate_redirects(meta);
+            let first = Self::translate_command(&self.parts, meta);
+            let mut commands = vec![ListFragment::new(vec![first, stdin])
+                .with_spaces()
+                .to_frag()];
+            for stage in &self.stages {
+                if let PipeStage::Command(parts) = stage {
+                    commands.push(Self::translate_command(parts, meta));
+                }
+            }
+            Self::translate_pipeline(meta, None, commands, outputs)
+        });
+        self.modifier.translate_command_timeout(meta, command)
+    }
+
+    fn translate_stages(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let is_statement = !meta.expr_ctx;
+        let is_local = meta.fun_meta.is_some();
+        let id = meta.gen_value_id();
+        let status = VarStmtFragment::new(PIPE_STATUS, Type::Int, raw_fragment!("0"))
+            .with_global_id(id)
+            .with_local(is_local)
+            .with_optimization_when_unused(false);
+        let status_expr = VarExprFragment::from_stmt(&status).to_frag();
+        meta.stmt_queue.push_back(status.to_frag());
+        // Stores the status code of the pipeline that has just finished when it failed
+        let on_failure = fragments!(
+            " || ",
+            VarStmtFragment::new(PIPE_STATUS, Type::Int, raw_fragment!("$?"))
+                .with_global_id(id)
+                .with_optimization_when_unused(false)
+                .to_frag()
+        );
+
+        let (stdin, outputs) = self.translate_redirects(meta);
+        let first = Self::translate_command(&self.parts, meta);
+        let mut input = None;
+        let mut commands = vec![ListFragment::new(vec![first, stdin])
+            .with_spaces()
+            .to_frag()];
+        for stage in &self.stages {
+            match stage {
+                PipeStage::Command(parts) => commands.push(Self::translate_command(parts, meta)),
+                PipeStage::Function {
+                    invocation,
+                    input_global_id,
+                    ..
+                } => {
+                    let is_running = !commands.is_empty();
+                    let value = if is_running {
+                        let pipeline = Self::translate_pipeline(
+                            meta,
+                            input.take(),
+                            std::mem::take(&mut commands),
+                            vec![],
+                        );
+                        SubprocessFragment::new(pipeline).to_frag()
+                    } else {
+                        input.take().unwrap_or(FragmentKind::Empty)
+                    };
+                    let stmt = VarStmtFragment::new(PIPE_INPUT, Type::Text, value)
+                        .with_global_id(*input_global_id)
+                        .with_local(is_local)
+                        .with_optimization_when_unused(false)
+                        .to_frag();
+                    if is_running {
+                        meta.stmt_queue
+                            .push_back(fragments!(stmt, on_failure.clone()));
+                    } else {
+                        meta.stmt_queue.push_back(stmt);
+                    }
+                    input = Some(meta.with_expr_ctx(true, |meta| invocation.translate(meta)));
+                }
+            }
+        }
+
+        let value = match (commands.is_empty(), is_statement) {
+            (true, _) => input.unwrap_or(FragmentKind::Empty),
+            (false, true) => {
+                let pipeline = Self::translate_pipeline(meta, input, commands, outputs);
+                meta.stmt_queue
+                    .push_back(fragments!("(", pipeline, ")", on_failure));
+                FragmentKind::Empty
+            }
+            (false, false) => {
+                let pipeline = Self::translate_pipeline(meta, input, commands, outputs);
+                let stmt = VarStmtFragment::new(
+                    "command",
+                    Type::Text,
+                    SubprocessFragment::new(pipeline).to_frag(),
+                )
+                .with_global_id(meta.gen_value_id())
+                .with_local(is_local)
+                .with_optimization_when_unused(false);
+                let var_expr = VarExprFragment::from_stmt(&stmt).to_frag();
+                meta.stmt_queue
+                    .push_back(fragments!(stmt.to_frag(), on_failure));
+                var_expr
+            }
+        };
+        let handler = self.failure_handler.translate_status(meta, status_expr);
+        if is_statement {
+            handler
+        } else {
+            meta.stmt_queue.push_back(handler);
+            value
+        }
     }
 }
 
@@ -27,16 +478,38 @@ impl SyntaxModule<ParserMetadata> for Command {
     fn new() -> Self {
         Command {
             parts: vec![],
-            modifier: CommandModifier::new_expr(),
+            stages: vec![],
+            redirects: vec![],
+            modifier: CommandModifier::new_expr().with_timeout(),
             failure_handler: FailureHandler::new(),
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         syntax(meta, &mut self.modifier)?;
-        self.modifier.use_modifiers(meta, |_this, meta| {
+        self.modifier.use_modifiers(meta, |modifier, meta| {
             let tok = meta.get_current_token();
-            self.parts = parse_interpolated_region(meta, &InterpolatedRegionType::Command)?;
+            self.parts = match parse_interpolated_region(meta, &InterpolatedRegionType::Command) {
+                Err(Failure::Quiet(_)) if modifier.timeout.is_some() => {
+                    return error!(meta, tok, "The `timeout` modifier can only be used with commands and blocks");
+                }
+                result => result?,
+            };
+            Self::parse_redirects(&mut self.redirects, &self.stages, meta)?;
+            while token(meta, "|").is_ok() {
+                if let Some(redirect) = self.redirects.iter().find(|redirect| redirect.kind != RedirectKind::Input) {
+                    return error!(meta, redirect.tok.clone(), "Output can only be redirected at the end of the pipeline");
+                }
+                self.stages.push(Self::parse_stage(meta)?);
+            }
+            Self::parse_redirects(&mut self.redirects, &self.stages, meta)?;
+            let has_function_stage = self.stages.iter().any(|stage| matches!(stage, PipeStage::Function { .. }));
+            if modifier.timeout.is_some() && has_function_stage {
+                return error!(meta, tok => {
+                    message: "Pipeline with a timeout can only consist of commands",
+                    comment: "Functions cannot be killed once the timeout passes"
+                });
+            }
 
             // Set position for failure handler
             let position = PositionInfo::from_between_tokens(meta, tok.clone(), meta.get_current_token());
@@ -60,10 +533,13 @@ impl SyntaxModule<ParserMetadata> for Command {
 
 impl TypeCheckModule for Command {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.modifier.typecheck(meta)?;
         self.modifier.use_modifiers(meta, |modifier, meta| {
             for part in self.parts.iter_mut() {
                 part.typecheck(meta)?;
             }
+            Self::typecheck_stages(&mut self.stages, meta)?;
+            Self::typecheck_redirects(&mut self.redirects, meta)?;
             if modifier.is_trust && self.failure_handler.is_question_mark {
                 let tok = meta.get_current_token();
                 return error!(meta, tok, "The '?' operator cannot be used with the 'trust' modifier because 'trust' ignores failure while '?' propagates it");
@@ -76,26 +552,13 @@ impl TypeCheckModule for Command {
 
 impl TranslateModule for Command {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
-        let is_silenced = self.modifier.is_silent || meta.silenced;
-        let is_suppress = self.modifier.is_suppress || meta.suppress;
-        let is_sudoed = self.modifier.is_sudo || meta.sudoed;
-
-        let translation = meta.with_silenced(is_silenced, |meta| {
-            meta.with_suppress(is_suppress, |meta| {
-                meta.with_sudoed(is_sudoed, |meta| {
-                    let parts = TextPart::to_interpolable_parts(&self.parts, meta);
-                    InterpolableFragment::new(parts, InterpolableRenderType::GlobalContext)
-                        .to_frag()
-                })
-            })
-        });
-
-        let silent = meta.with_silenced(is_silenced, |meta| meta.gen_silent().to_frag());
-        let suppress = meta.with_suppress(is_suppress, |meta| meta.gen_suppress().to_frag());
-        let sudo_prefix = meta.with_sudoed(is_sudoed, |meta| meta.gen_sudo_prefix().to_frag());
-        let translation = ListFragment::new(vec![sudo_prefix, translation, suppress, silent])
-            .with_spaces()
-            .to_frag();
+        let translation = if self.modifier.timeout.is_some() {
+            self.translate_job(meta)
+        } else if !self.stages.is_empty() {
+            return self.with_modifiers(meta, |meta| self.translate_stages(meta));
+        } else {
+            self.with_modifiers(meta, |meta| self.translate_single(meta))
+        };
 
         let handler = self.failure_handler.translate(meta);
         let is_statement = !meta.expr_ctx;
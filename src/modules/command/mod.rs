@@ -1,2 +1,4 @@
+pub mod capture;
 pub mod cmd;
 pub mod modifier;
+pub mod spawn;
@@ -1,21 +1,39 @@
 use crate::modules::block::Block;
+use crate::modules::condition::failure_handler::FailureHandler;
+use crate::modules::expression::expr::Expr;
+use crate::modules::expression::literal::duration::translate_duration_seconds;
 use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::job::{translate_input_job_start, translate_job_kill, translate_job_start};
+use crate::utils::context::Context;
+use crate::utils::metadata::ShellLoop;
+use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use amber_meta::ContextManager;
 use heraclitus_compiler::prelude::*;
 
+/// Status code of a command or a block that has been killed once its timeout passed
+pub const TIMEOUT_STATUS: &str = "124";
+
 #[derive(Debug, Clone, ContextManager)]
 pub struct CommandModifier {
     pub block: Option<Box<Block>>,
+    /// Handles the failure of the block that runs with a timeout
+    pub failure_handler: Option<Box<FailureHandler>>,
+    /// Duration after which the command or the block is killed together with the processes it started.
+    /// When the standard input is a terminal, only the command or the block itself is killed.
+    pub timeout: Option<Box<Expr>>,
     pub trust_position: Option<PositionInfo>,
     pub silent_position: Option<PositionInfo>,
     pub suppress_position: Option<PositionInfo>,
     pub sudo_position: Option<PositionInfo>,
+    pub timeout_position: Option<PositionInfo>,
     #[context]
     pub is_trust: bool,
     pub is_silent: bool,
     pub is_suppress: bool,
     pub is_sudo: bool,
+    allows_timeout: bool,
 }
 
 #[derive(Debug, Clone, AutoKeyword)]
@@ -42,6 +60,12 @@ pub struct Suppress;
 #[allow(dead_code)]
 pub struct Trust;
 
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "timeout"]
+#[kind = "stmt"]
+#[allow(dead_code)]
+pub struct Timeout;
+
 #[derive(Debug, Clone, AutoKeyword)]
 #[keyword = "unsafe"]
 #[kind = "stmt"]
@@ -52,17 +76,27 @@ impl CommandModifier {
     pub fn new_expr() -> Self {
         CommandModifier {
             block: None,
+            failure_handler: None,
+            timeout: None,
             is_trust: false,
             is_silent: false,
             is_suppress: false,
             is_sudo: false,
+            allows_timeout: false,
             trust_position: None,
             silent_position: None,
             suppress_position: None,
             sudo_position: None,
+            timeout_position: None,
         }
     }
 
+    /// Allows the `timeout` modifier that kills the command
+    pub fn with_timeout(mut self) -> Self {
+        self.allows_timeout = true;
+        self
+    }
+
     pub fn use_modifiers<F>(&mut self, meta: &mut ParserMetadata, context: F) -> SyntaxResult
     where
         F: FnOnce(&mut Self, &mut ParserMetadata) -> SyntaxResult,
@@ -145,6 +179,27 @@ impl CommandModifier {
                             Some(PositionInfo::from_token(meta, Some(tok.clone())));
                         meta.increment_index();
                     }
+                    "timeout" => {
+                        // Other modules do not run in a process that could be killed
+                        if !self.allows_timeout {
+                            return Err(Failure::Quiet(PositionInfo::from_metadata(meta)));
+                        }
+                        if self.timeout.is_some() {
+                            return error!(
+                                meta,
+                                Some(tok.clone()),
+                                "You already declared `timeout` modifier before"
+                            );
+                        }
+                        self.timeout_position =
+                            Some(PositionInfo::from_token(meta, Some(tok.clone())));
+                        meta.increment_index();
+                        token(meta, "(")?;
+                        let mut timeout = Expr::new();
+                        syntax(meta, &mut timeout)?;
+                        token(meta, ")")?;
+                        self.timeout = Some(Box::new(timeout));
+                    }
                     _ => break,
                 },
                 None => return Err(Failure::Quiet(PositionInfo::from_metadata(meta))),
@@ -152,6 +207,146 @@ impl CommandModifier {
         }
         Ok(())
     }
+
+    fn parse_failure_handler(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let mut failure_handler = FailureHandler::new();
+        if let Err(err) = syntax(meta, &mut failure_handler) {
+            return match err {
+                Failure::Quiet(pos) => error_pos!(meta, pos => {
+                    message: "The block with a timeout can fail and requires explicit failure handling. Use '?', 'failed', 'succeeded', or 'exited' to manage its result.",
+                    comment: format!("The block fails with the status code {TIMEOUT_STATUS} when it is killed because of the timeout")
+                }),
+                _ => Err(err),
+            };
+        }
+        self.failure_handler = Some(Box::new(failure_handler));
+        Ok(())
+    }
+
+    fn typecheck_timeout(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let Some(timeout) = self.timeout.as_mut() else {
+            return Ok(());
+        };
+        timeout.typecheck(meta)?;
+        let timeout_type = timeout.get_type();
+        if timeout_type != Type::duration() {
+            let position = timeout.get_position();
+            return error_pos!(meta, position => {
+                message: "Timeout of the command must be of type Duration",
+                comment: format!("Given type: {timeout_type}. Use a duration literal such as '30s'")
+            });
+        }
+        Ok(())
+    }
+
+    /// Runs the job in the background and kills it once the timeout passes.
+    /// Returns the statements that run and wait for the job and the status code of the job.
+    fn translate_timeout(
+        &self,
+        meta: &mut TranslateMetadata,
+        mut job: Vec<FragmentKind>,
+    ) -> (Vec<FragmentKind>, FragmentKind) {
+        let Some(timeout) = &self.timeout else {
+            return (job, raw_fragment!("$?"));
+        };
+        let is_local = meta.fun_meta.is_some();
+        let value = timeout.translate(meta);
+        let millis =
+            VarStmtFragment::new("__timeout", Type::Int, value).with_global_id(meta.gen_value_id());
+        let millis = meta.push_ephemeral_variable(millis);
+        let seconds = translate_duration_seconds(millis.with_quotes(false).to_frag());
+        let id = meta.gen_value_id();
+        let var = |name: &str, value: FragmentKind| {
+            let stmt = VarStmtFragment::new(name, Type::Int, value)
+                .with_global_id(id)
+                .with_local(is_local)
+                .with_optimization_when_unused(false);
+            let expr = VarExprFragment::from_stmt(&stmt).to_frag();
+            (stmt.to_frag(), expr)
+        };
+        let (pid, pid_expr) = var("__timeout_pid", raw_fragment!("$!"));
+        let (watcher, watcher_expr) = var("__timeout_watcher", raw_fragment!("$!"));
+        let (status, status_expr) = var("__timeout_status", raw_fragment!("$?"));
+        let killed = VarStmtFragment::new(
+            "__timeout_status",
+            Type::Int,
+            raw_fragment!("{TIMEOUT_STATUS}"),
+        )
+        .with_global_id(id)
+        .with_optimization_when_unused(false)
+        .to_frag();
+        let start = job.pop().unwrap_or_default();
+        job.push(fragments!(start, " <&3 3<&-"));
+        let mut statements = translate_input_job_start(meta, job);
+        statements.push(pid);
+        // The watcher ignores the termination once it started killing the job
+        // so that its status code tells whether the timeout has passed
+        let watcher_job = translate_job_start(
+            meta,
+            vec![fragments!(
+                "{ sleep ",
+                seconds,
+                "; trap '' TERM; ",
+                translate_job_kill(pid_expr.clone()),
+                "; } 3<&- </dev/null >/dev/null 2>&1"
+            )],
+        );
+        statements.extend(watcher_job);
+        statements.extend([
+            watcher,
+            fragments!("wait ", pid_expr),
+            status,
+            translate_job_kill(watcher_expr.clone()),
+            fragments!("wait ", watcher_expr, " && ", killed),
+        ]);
+        (statements, status_expr)
+    }
+
+    /// Wraps the translated command so that it is killed once the timeout passes.
+    /// The status code of the wrapped command is the one of the command or the timeout status code.
+    pub fn translate_command_timeout(
+        &self,
+        meta: &mut TranslateMetadata,
+        command: FragmentKind,
+    ) -> FragmentKind {
+        if self.timeout.is_none() {
+            return command;
+        }
+        let (mut statements, status) =
+            self.translate_timeout(meta, vec![fragments!("{ ", command, "; }")]);
+        statements.push(fragments!("(exit ", status, ")"));
+        // The original standard input is passed to the job that otherwise reads from /dev/null
+        let mut group = vec![raw_fragment!("{{")];
+        for statement in statements {
+            group.push(fragments!(" ", statement, ";"));
+        }
+        group.push(raw_fragment!(" }} 3<&0"));
+        ListFragment::new(group).to_frag()
+    }
+
+    fn translate_block_timeout(&self, meta: &mut TranslateMetadata, block: &Block) -> FragmentKind {
+        let body = meta.with_loop(ShellLoop::Job, |meta| block.translate(meta));
+        let job = vec![
+            fragments!("("),
+            BlockFragment::new(vec![body], true).to_frag(),
+            fragments!(")"),
+        ];
+        let (statements, status) = self.translate_timeout(meta, job);
+        let handler = match &self.failure_handler {
+            Some(failure_handler) => failure_handler.translate_status(meta, status),
+            None => FragmentKind::Empty,
+        };
+        BlockFragment::new(
+            vec![
+                fragments!("{"),
+                BlockFragment::new(statements, true).to_frag(),
+                fragments!("} 3<&0"),
+                handler,
+            ],
+            false,
+        )
+        .to_frag()
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for CommandModifier {
@@ -160,14 +355,18 @@ impl SyntaxModule<ParserMetadata> for CommandModifier {
     fn new() -> Self {
         CommandModifier {
             block: Some(Box::new(Block::new().with_no_indent())),
+            failure_handler: None,
+            timeout: None,
             is_trust: false,
             is_silent: false,
             is_suppress: false,
             is_sudo: false,
+            allows_timeout: true,
             trust_position: None,
             silent_position: None,
             suppress_position: None,
             sudo_position: None,
+            timeout_position: None,
         }
     }
 
@@ -175,7 +374,17 @@ impl SyntaxModule<ParserMetadata> for CommandModifier {
         self.parse_modifier_sequence(meta)?;
         if let Some(mut block) = self.block.take() {
             return self.use_modifiers(meta, |this, meta| {
-                syntax(meta, &mut *block)?;
+                if this.timeout.is_some() {
+                    // The block runs as a background job and failures that are not handled end it
+                    meta.with_context_fn(Context::set_is_job_ctx, true, |meta| {
+                        meta.with_context_fn(Context::set_is_try_ctx, true, |meta| {
+                            syntax(meta, &mut *block)
+                        })
+                    })?;
+                    this.parse_failure_handler(meta)?;
+                } else {
+                    syntax(meta, &mut *block)?;
+                }
                 this.block = Some(block);
                 Ok(())
             });
@@ -186,10 +395,20 @@ impl SyntaxModule<ParserMetadata> for CommandModifier {
 
 impl TypeCheckModule for CommandModifier {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.typecheck_timeout(meta)?;
         if let Some(mut block) = self.block.take() {
+            let job_scope = match self.timeout {
+                Some(_) => Some(meta.context.scopes.len()),
+                None => meta.context.job_scope,
+            };
             return self.use_modifiers(meta, |this, meta| {
-                block.typecheck(meta)?;
+                meta.with_context_fn(Context::set_job_scope, job_scope, |meta| {
+                    block.typecheck(meta)
+                })?;
                 this.block = Some(block);
+                if let Some(failure_handler) = this.failure_handler.as_mut() {
+                    failure_handler.typecheck(meta)?;
+                }
                 Ok(())
             });
         }
@@ -203,7 +422,11 @@ impl TranslateModule for CommandModifier {
             meta.silenced = self.is_silent;
             meta.suppress = self.is_suppress;
             meta.sudoed = self.is_sudo;
-            let result = block.translate(meta);
+            let result = if self.timeout.is_some() {
+                self.translate_block_timeout(meta, block)
+            } else {
+                block.translate(meta)
+            };
             meta.silenced = false;
             meta.suppress = false;
             meta.sudoed = false;
@@ -0,0 +1,76 @@
+use super::cmd::Command;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::job::translate_job_start;
+use crate::utils::context::Context;
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Runs the command in the background and returns the job that can be awaited or killed.
+/// The job runs in its own process group, so killing it also stops the processes it started.
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "spawn"]
+pub struct Spawn {
+    command: Box<Command>,
+}
+
+impl Typed for Spawn {
+    fn get_type(&self) -> Type {
+        Type::job()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Spawn {
+    syntax_name!("Spawn");
+
+    fn new() -> Self {
+        Spawn {
+            command: Box::new(Command::new()),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "spawn")?;
+        // The failure of the job is handled when it is awaited
+        meta.with_context_fn(Context::set_is_trust_ctx, true, |meta| {
+            syntax(meta, &mut *self.command)
+        })?;
+        if self.command.has_failure_handler() {
+            return error!(meta, tok => {
+                message: "Spawned command cannot have a failure handler",
+                comment: "Handle the failure of the job when it is awaited with 'await job failed { ... }'"
+            });
+        }
+        if self.command.has_function_stage() {
+            return error!(meta, tok => {
+                message: "Spawned pipeline can only consist of commands",
+                comment: "Functions cannot run in the background"
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Spawn {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.command.typecheck(meta)
+    }
+}
+
+impl TranslateModule for Spawn {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let command = self.command.translate_job(meta);
+        // The job runs in its own process group, so that killing it also stops its processes.
+        // It reads from /dev/null as it would without the process group.
+        let job = fragments!("(", command, ") </dev/null");
+        let statements = translate_job_start(meta, vec![job]);
+        meta.stmt_queue.extend(statements);
+        let id = meta.gen_value_id();
+        let job = VarStmtFragment::new("__job", Type::Int, raw_fragment!("$!")).with_global_id(id);
+        meta.push_ephemeral_variable(job).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(Spawn);
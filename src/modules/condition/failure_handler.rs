@@ -1,4 +1,7 @@
 use crate::modules::block::Block;
+use crate::modules::condition::try_stmt::translate_try_jump;
+use crate::modules::function::defer::translate_return;
+use crate::modules::function::fail::FAIL_MESSAGE;
 use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::modules::variable::variable_name_extensions;
@@ -48,6 +51,16 @@ pub struct FailureHandler {
     pub is_parsed: bool,
     pub failure_type: FailureType,
     pub is_question_mark: bool,
+    /// Failure is handled by the '??' or '!' operator that follows the call
+    pub is_implicit: bool,
+    /// Failure is handled by the enclosing try block
+    pub is_try: bool,
+    /// Handler of a try block that receives the failure message of its body
+    is_try_handler: bool,
+    /// Failure is ignored because the handler was omitted in a trusted context
+    is_trusted: bool,
+    /// Propagated failure ends the background job of a parallel loop or a block with a timeout
+    is_job: bool,
     error_position: Option<PositionInfo>,
     function_name: Option<String>,
     is_main: bool,
@@ -55,6 +68,9 @@ pub struct FailureHandler {
     param_name: String,
     param_name_tok: Option<Token>,
     param_global_id: Option<usize>,
+    message_name: String,
+    message_name_tok: Option<Token>,
+    message_global_id: Option<usize>,
 }
 
 impl FailureHandler {
@@ -65,6 +81,36 @@ impl FailureHandler {
     pub fn set_function_name(&mut self, name: String) {
         self.function_name = Some(name);
     }
+
+    pub fn set_try_handler(&mut self) {
+        self.is_try_handler = true;
+    }
+
+    /// Whether the handler was written by the user
+    pub fn is_explicit(&self) -> bool {
+        self.is_parsed && !self.is_trusted && !self.is_try
+    }
+
+    /// Determines if the failure can carry a message set by a failing function
+    fn has_message(&self) -> bool {
+        self.function_name.is_some() || self.is_try_handler
+    }
+
+    /// Assigns the failure message to the second parameter of the handler
+    fn translate_message_assignment(&self) -> FragmentKind {
+        if self.message_name.is_empty() {
+            return FragmentKind::Empty;
+        }
+        // Only functions can fail with a message
+        let message = if self.has_message() {
+            VarExprFragment::new(FAIL_MESSAGE, Type::Text).to_frag()
+        } else {
+            fragments!("''")
+        };
+        VarStmtFragment::new(&self.message_name, Type::Text, message)
+            .with_global_id(self.message_global_id)
+            .to_frag()
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for FailureHandler {
@@ -75,6 +121,11 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
             is_parsed: false,
             failure_type: FailureType::Failed,
             is_question_mark: false,
+            is_implicit: false,
+            is_try: false,
+            is_try_handler: false,
+            is_trusted: false,
+            is_job: false,
             is_main: false,
             function_name: None,
             error_position: None,
@@ -82,6 +133,9 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
             param_name: String::new(),
             param_name_tok: None,
             param_global_id: None,
+            message_name: String::new(),
+            message_name_tok: None,
+            message_global_id: None,
         }
     }
 
@@ -90,14 +144,21 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
 
         // Check for ? operator first
         if token(meta, "?").is_ok() {
-            if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
+            if !meta.context.is_fun_ctx && !meta.context.is_main_ctx && !meta.context.is_job_ctx {
                 return error!(
                     meta,
                     tok,
                     "The '?' operator can only be used in the main block or inside a function body"
                 );
             }
+            if meta.context.is_defer_ctx {
+                return error!(
+                    meta,
+                    tok, "The '?' operator cannot be used inside of a defer block"
+                );
+            }
             self.is_question_mark = true;
+            self.is_job = meta.context.is_job_ctx;
             self.failure_type = FailureType::Failed;
         } else {
             let keyword = ["failed", "succeeded", "exited"]
@@ -128,6 +189,11 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
 
                                 self.param_name_tok = meta.get_current_token();
                                 self.param_name = variable(meta, variable_name_extensions())?;
+                                // The second parameter receives the failure message
+                                if token(meta, ",").is_ok() {
+                                    self.message_name_tok = meta.get_current_token();
+                                    self.message_name = variable(meta, variable_name_extensions())?;
+                                }
                                 token(meta, ")")?;
 
                                 // Parse the block (scope and variable will be added in typecheck)
@@ -166,6 +232,11 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
 
                                 self.param_name_tok = meta.get_current_token();
                                 self.param_name = variable(meta, variable_name_extensions())?;
+                                // The second parameter receives the failure message
+                                if token(meta, ",").is_ok() {
+                                    self.message_name_tok = meta.get_current_token();
+                                    self.message_name = variable(meta, variable_name_extensions())?;
+                                }
                                 token(meta, ")")?;
 
                                 // Parse the block (scope and variable will be added in typecheck)
@@ -183,7 +254,40 @@ impl SyntaxModule<ParserMetadata> for FailureHandler {
                     unimplemented!("Keyword '{keyword}' is not yet implemented")
                 }
                 None => {
+                    // The '??' operator handles the failure and '!' propagates it
+                    let next_word = tok.as_ref().map(|tok| tok.word.as_str());
+                    if matches!(next_word, Some("??" | "!")) {
+                        if next_word == Some("!")
+                            && !meta.context.is_fun_ctx
+                            && !meta.context.is_main_ctx
+                            && !meta.context.is_job_ctx
+                        {
+                            return error!(
+                                meta,
+                                tok,
+                                "The '!' operator can only be used in the main block or inside a function body"
+                            );
+                        }
+                        if next_word == Some("!") && meta.context.is_defer_ctx {
+                            return error!(
+                                meta,
+                                tok, "The '!' operator cannot be used inside of a defer block"
+                            );
+                        }
+                        self.is_question_mark = next_word == Some("!");
+                        self.is_job = meta.context.is_job_ctx;
+                        self.is_implicit = true;
+                        self.is_main = meta.context.is_main_ctx;
+                        self.is_parsed = true;
+                        return Ok(());
+                    }
                     if meta.context.is_trust_ctx {
+                        self.is_trusted = true;
+                        self.is_main = meta.context.is_main_ctx;
+                        self.is_parsed = true;
+                        return Ok(());
+                    } else if meta.context.is_try_ctx {
+                        self.is_try = true;
                         self.is_main = meta.context.is_main_ctx;
                         self.is_parsed = true;
                         return Ok(());
@@ -235,6 +339,12 @@ impl TypeCheckModule for FailureHandler {
                     VariableDeclWarn::from_token(meta, self.param_name_tok.clone()),
                 );
                 self.param_global_id = meta.add_var(var);
+                if !self.message_name.is_empty() {
+                    let var = VariableDecl::new(self.message_name.clone(), Type::Text).with_warn(
+                        VariableDeclWarn::from_token(meta, self.message_name_tok.clone()),
+                    );
+                    self.message_global_id = meta.add_var(var);
+                }
                 self.block.typecheck(meta)
             })
         } else {
@@ -245,6 +355,17 @@ impl TypeCheckModule for FailureHandler {
 
 impl TranslateModule for FailureHandler {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        self.translate_status(meta, fragments!("$?"))
+    }
+}
+
+impl FailureHandler {
+    /// Handles the failure with the status code read from given fragment
+    pub fn translate_status(
+        &self,
+        meta: &mut TranslateMetadata,
+        status: FragmentKind,
+    ) -> FragmentKind {
         if !self.is_parsed {
             return FragmentKind::Empty;
         }
@@ -254,36 +375,46 @@ impl TranslateModule for FailureHandler {
         let block = self.block.translate(meta);
         meta.expr_ctx = is_expr_ctx;
         // the condition of '$?' clears the status code thus we need to store it in a variable
-        let status_variable_stmt = VarStmtFragment::new("__status", Type::Int, fragments!("$?"));
+        let status_variable_stmt = VarStmtFragment::new("__status", Type::Int, status);
         let status_variable_expr = VarExprFragment::from_stmt(&status_variable_stmt);
 
+        if self.is_try {
+            return BlockFragment::new(
+                vec![
+                    status_variable_stmt.to_frag(),
+                    fragments!(
+                        "if [ ",
+                        status_variable_expr.clone().to_frag(),
+                        " != 0 ]; then"
+                    ),
+                    translate_try_jump(meta, status_variable_expr.to_frag(), self.has_message()),
+                    fragments!("fi"),
+                ],
+                false,
+            )
+            .to_frag();
+        }
+
         if self.is_question_mark {
-            // Set default return value if failure happened in a function
-            let clear_return = if !self.is_main {
-                let fun_meta = meta
-                    .fun_meta
-                    .as_ref()
-                    .expect("Function name and return type not set");
-                let stmt = VarStmtFragment::new(
-                    &fun_meta.mangled_name(),
-                    fun_meta.get_type(),
-                    fun_meta.default_return(),
+            let exit = if self.is_job {
+                BlockFragment::new(
+                    vec![fragments!("exit ", status_variable_expr.clone().to_frag())],
+                    true,
                 )
-                .with_optimization_when_unused(false);
-                stmt.to_frag()
+                .to_frag()
             } else {
-                FragmentKind::Empty
+                translate_failure_exit(
+                    meta,
+                    self.is_main,
+                    status_variable_expr.clone().to_frag(),
+                    self.has_message(),
+                )
             };
-            let ret = if self.is_main { "exit" } else { "return" };
-            let ret = fragments!(
-                raw_fragment!("{ret} "),
-                status_variable_expr.clone().to_frag()
-            );
             return BlockFragment::new(
                 vec![
                     status_variable_stmt.to_frag(),
                     fragments!("if [ ", status_variable_expr.to_frag(), " != 0 ]; then"),
-                    BlockFragment::new(vec![clear_return, ret], true).to_frag(),
+                    exit,
                     fragments!("fi"),
                 ],
                 false,
@@ -307,6 +438,7 @@ impl TranslateModule for FailureHandler {
                                 status_variable_expr.clone().to_frag(),
                             )
                             .with_global_id(self.param_global_id);
+                            let message_assignment = self.translate_message_assignment();
 
                             BlockFragment::new(
                                 vec![
@@ -317,6 +449,7 @@ impl TranslateModule for FailureHandler {
                                         " != 0 ]; then"
                                     ),
                                     param_assignment.to_frag(),
+                                    message_assignment,
                                     block,
                                     fragments!("fi"),
                                 ],
@@ -360,11 +493,13 @@ impl TranslateModule for FailureHandler {
                                 status_variable_expr.clone().to_frag(),
                             )
                             .with_global_id(self.param_global_id);
+                            let message_assignment = self.translate_message_assignment();
 
                             BlockFragment::new(
                                 vec![
                                     status_variable_stmt.to_frag(),
                                     param_assignment.to_frag(),
+                                    message_assignment,
                                     block,
                                 ],
                                 false,
@@ -380,3 +515,45 @@ impl TranslateModule for FailureHandler {
         }
     }
 }
+
+/// Leaves the enclosing function with given status code or exits the script in the main block.
+/// The message of a failure propagated from a function call is kept, otherwise it is cleared.
+pub fn translate_failure_exit(
+    meta: &mut TranslateMetadata,
+    is_main: bool,
+    status: FragmentKind,
+    is_propagated: bool,
+) -> FragmentKind {
+    // Set default return value if failure happened in a function
+    let clear_return = if !is_main {
+        let fun_meta = meta
+            .fun_meta
+            .as_ref()
+            .expect("Function name and return type not set");
+        let stmt = VarStmtFragment::new(
+            &fun_meta.mangled_name(),
+            fun_meta.get_type(),
+            fun_meta.default_return(),
+        )
+        .with_optimization_when_unused(false);
+        stmt.to_frag()
+    } else {
+        FragmentKind::Empty
+    };
+    let message = match (is_main, is_propagated) {
+        // Uncaught failures print their message before the script exits
+        (true, true) => raw_fragment!(
+            "[ -n \"${{{FAIL_MESSAGE}}}\" ] && printf '%s\\n' \"${{{FAIL_MESSAGE}}}\" >&2"
+        ),
+        (false, false) => VarStmtFragment::new(FAIL_MESSAGE, Type::Text, fragments!("''"))
+            .with_optimization_when_unused(false)
+            .to_frag(),
+        _ => FragmentKind::Empty,
+    };
+    let ret = if is_main {
+        fragments!("exit ", status)
+    } else {
+        translate_return(meta, status)
+    };
+    BlockFragment::new(vec![clear_return, message, ret], true).to_frag()
+}
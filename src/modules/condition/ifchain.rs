@@ -1,9 +1,9 @@
+use super::warn_dead_code;
 use crate::fragments;
 use crate::modules::block::Block;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::statement::comment::Comment;
-use crate::utils::cc_flags::{get_ccflag_name, CCFlags};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -19,21 +19,6 @@ pub struct IfChain {
 
 crate::impl_documentation_noop!(IfChain);
 
-impl IfChain {
-    fn warn_dead_code(meta: &mut ParserMetadata, pos: PositionInfo, reason: &str) {
-        if meta.context.cc_flags.contains(&CCFlags::AllowDeadCode) {
-            return;
-        }
-        let flag_name = get_ccflag_name(CCFlags::AllowDeadCode);
-        let message = Message::new_warn_at_position(meta, pos)
-            .message(reason)
-            .comment(format!(
-                "To suppress this warning, use '{flag_name}' compiler flag"
-            ));
-        meta.add_message(message);
-    }
-}
-
 impl SyntaxModule<ParserMetadata> for IfChain {
     syntax_name!("If Condition");
 
@@ -118,7 +103,7 @@ impl TypeCheckModule for IfChain {
             let pos = cond.get_position();
 
             if chain_deadcode {
-                Self::warn_dead_code(
+                warn_dead_code(
                     meta,
                     pos,
                     "Condition is unreachable, previous condition is always true",
@@ -139,7 +124,7 @@ impl TypeCheckModule for IfChain {
                     first_true_pos = Some(pos);
                 }
                 Some(false) => {
-                    Self::warn_dead_code(
+                    warn_dead_code(
                         meta,
                         pos,
                         "Condition is always false, block will never execute",
@@ -165,7 +150,7 @@ impl TypeCheckModule for IfChain {
         if chain_deadcode {
             if self.false_block.is_some() {
                 if let Some(pos) = first_true_pos {
-                    Self::warn_dead_code(
+                    warn_dead_code(
                         meta,
                         pos,
                         "Condition is always true, 'else' block will never execute",
@@ -0,0 +1,115 @@
+use super::match_utils::{render_missing_cases, translate_condition, MatchArms};
+use crate::fragments;
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "match"]
+#[kind = "stmt"]
+pub struct Match {
+    arms: MatchArms<Block>,
+}
+
+impl Match {
+    fn translate_case(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let subject = self.arms.subject.translate(meta);
+        let patterns = self.arms.translate_patterns(meta);
+        let mut arms = vec![];
+        for (pattern, (_, block)) in patterns.into_iter().zip(&self.arms.arms) {
+            arms.push(fragments!(pattern, ")"));
+            arms.push(block.translate(meta));
+            arms.push(fragments!(";;"));
+        }
+        if let Some(else_block) = &self.arms.else_arm {
+            arms.push(fragments!("*)"));
+            arms.push(else_block.translate(meta));
+            arms.push(fragments!(";;"));
+        }
+        BlockFragment::new(
+            vec![
+                fragments!("case ", subject, " in"),
+                BlockFragment::new(arms, true).to_frag(),
+                fragments!("esac"),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+
+    fn translate_if_chain(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let conditions = self.arms.translate_conditions(meta);
+        let mut result = vec![];
+        for (index, (condition, (_, block))) in
+            conditions.into_iter().zip(&self.arms.arms).enumerate()
+        {
+            let keyword = if index == 0 { "if" } else { "elif" };
+            result.push(translate_condition(keyword, condition));
+            result.push(block.translate(meta));
+        }
+        if let Some(else_block) = &self.arms.else_arm {
+            result.push(fragments!("else"));
+            result.push(else_block.translate(meta));
+        }
+        result.push(fragments!("fi"));
+        BlockFragment::new(result, false).to_frag()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Match {
+    syntax_name!("Match");
+
+    fn new() -> Self {
+        Match {
+            arms: MatchArms::new(),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.arms.parse(meta, |meta| {
+            let mut block = Block::new().with_needs_noop().with_condition();
+            syntax(meta, &mut block)?;
+            Ok(block)
+        })
+    }
+}
+
+impl TypeCheckModule for Match {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let missing = self
+            .arms
+            .typecheck(meta, "'else' block will never execute")?;
+        match missing {
+            Some(missing) if !missing.is_empty() => {
+                let message = Message::new_warn_at_token(meta, self.arms.tok.clone())
+                    .message(format!(
+                        "Match is not exhaustive, missing {}",
+                        render_missing_cases(&missing)
+                    ))
+                    .comment("Add the missing cases or an 'else' block");
+                meta.add_message(message);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for Match {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        if self.arms.arms.is_empty() {
+            return match &self.arms.else_arm {
+                Some(else_block) => else_block.translate(meta),
+                None => FragmentKind::Empty,
+            };
+        }
+        if self.arms.has_ranges() {
+            self.translate_if_chain(meta)
+        } else {
+            self.translate_case(meta)
+        }
+    }
+}
+
+crate::impl_documentation_noop!(Match);
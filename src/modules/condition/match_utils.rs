@@ -0,0 +1,393 @@
+use super::warn_dead_code;
+use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::prelude::*;
+use crate::modules::types::{parse_type, resolve_type, Type, Typed};
+use crate::translate::compute::ArithOp;
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// A single case of a match arm
+#[derive(Debug, Clone)]
+pub enum MatchCase {
+    /// Value that the subject is compared with, such as an enum variant or a literal
+    Value(Expr),
+    /// Range of integers that has to contain the subject
+    Range(Expr),
+    /// Type test that is resolved at compile time
+    Type(Type, Option<Token>),
+}
+
+impl MatchCase {
+    fn parse(meta: &mut ParserMetadata) -> Result<Self, Failure> {
+        if token(meta, "is").is_ok() {
+            let tok = meta.get_current_token();
+            let kind = parse_type(meta)?;
+            return Ok(MatchCase::Type(kind, tok));
+        }
+        let mut expr = Expr::new();
+        syntax(meta, &mut expr)?;
+        match expr.value {
+            Some(ExprType::Range(_)) => Ok(MatchCase::Range(expr)),
+            _ => Ok(MatchCase::Value(expr)),
+        }
+    }
+
+    fn get_position(&self, meta: &ParserMetadata) -> PositionInfo {
+        match self {
+            MatchCase::Value(expr) | MatchCase::Range(expr) => expr.get_position(),
+            MatchCase::Type(_, tok) => PositionInfo::from_token(meta, tok.clone()),
+        }
+    }
+
+    /// Typechecks the case and returns whether it is known to match at compile time
+    fn typecheck(
+        &mut self,
+        meta: &mut ParserMetadata,
+        subject: &Expr,
+        handled: &mut HashSet<String>,
+    ) -> Result<Option<bool>, Failure> {
+        let subject_type = subject.get_type();
+        match self {
+            MatchCase::Value(expr) => {
+                expr.typecheck(meta)?;
+                if !matches!(subject_type, Type::Text | Type::Int | Type::Enum(_)) {
+                    let pos = subject.get_position();
+                    return error_pos!(meta, pos => {
+                        message: format!("Cannot match values of type '{subject_type}'"),
+                        comment: "Only values of type 'Text', 'Int' and enums can be compared with cases, use 'is' cases to match types"
+                    });
+                }
+                if expr.get_type() != subject_type {
+                    let pos = expr.get_position();
+                    let message = match subject_type {
+                        Type::Enum(_) => {
+                            format!("Match case has to be a variant of enum '{subject_type}'")
+                        }
+                        _ => format!("Match case has to be of type '{subject_type}'"),
+                    };
+                    return error_pos!(meta, pos => {
+                        message: message,
+                        comment: format!("Given case is of type '{}'", expr.get_type())
+                    });
+                }
+                let handled_case = match &expr.value {
+                    Some(ExprType::EnumVariant(variant)) => {
+                        Some(format!("{subject_type}.{}", variant.variant))
+                    }
+                    _ => expr.get_integer_value().map(|value| value.to_string()),
+                };
+                if let Some(case) = handled_case {
+                    if !handled.insert(case.clone()) {
+                        let pos = expr.get_position();
+                        warn_dead_code(
+                            meta,
+                            pos,
+                            &format!("Case '{case}' is unreachable, it is already handled"),
+                        );
+                    }
+                }
+                Ok(None)
+            }
+            MatchCase::Range(expr) => {
+                expr.typecheck(meta)?;
+                if subject_type != Type::Int {
+                    let pos = expr.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Range cases can only be used to match values of type 'Int'",
+                        comment: format!("Given value is of type '{subject_type}'")
+                    });
+                }
+                Ok(None)
+            }
+            MatchCase::Type(kind, tok) => {
+                *kind = resolve_type(meta, kind, tok.clone())?;
+                if !subject_type.can_intersect(kind) {
+                    let pos = PositionInfo::from_token(meta, tok.clone());
+                    warn_dead_code(
+                        meta,
+                        pos,
+                        &format!("Value is never of type '{kind}', case will never match"),
+                    );
+                }
+                // Same as the `is` operator, types are resolved at compile time
+                Ok(Some(subject_type == *kind))
+            }
+        }
+    }
+}
+
+/// The subject and arms of a match, shared by the match statement and the match expression
+#[derive(Debug, Clone)]
+pub struct MatchArms<T> {
+    pub subject: Box<Expr>,
+    pub arms: Vec<(Vec<MatchCase>, T)>,
+    pub else_arm: Option<Box<T>>,
+    pub tok: Option<Token>,
+    pub else_tok: Option<Token>,
+}
+
+impl<T: TypeCheckModule + TranslateModule> MatchArms<T> {
+    pub fn new() -> Self {
+        MatchArms {
+            subject: Box::new(Expr::new()),
+            arms: vec![],
+            else_arm: None,
+            tok: None,
+            else_tok: None,
+        }
+    }
+
+    /// Parses the match using the given parser for the body of every arm
+    pub fn parse<P>(&mut self, meta: &mut ParserMetadata, mut parse_arm: P) -> SyntaxResult
+    where
+        P: FnMut(&mut ParserMetadata) -> Result<T, Failure>,
+    {
+        self.tok = meta.get_current_token();
+        token(meta, "match")?;
+        syntax(meta, &mut *self.subject)?;
+        token(meta, "{")?;
+        loop {
+            skip_match_separators(meta);
+            if token(meta, "}").is_ok() {
+                return Ok(());
+            }
+            // Handle else keyword
+            self.else_tok = meta.get_current_token();
+            if token(meta, "else").is_ok() {
+                self.else_arm = Some(Box::new(parse_arm(meta)?));
+                skip_match_separators(meta);
+                if token(meta, "}").is_err() {
+                    return error!(
+                        meta,
+                        meta.get_current_token(),
+                        "Expected `else` to be the last case in the match"
+                    );
+                }
+                return Ok(());
+            }
+            let cases = parse_match_cases(meta)?;
+            let arm = parse_arm(meta)?;
+            self.arms.push((cases, arm));
+        }
+    }
+
+    /// Typechecks the match and returns the missing cases if it is not exhaustive.
+    /// The `unused_else` describes what happens to the 'else' arm when it is dead code.
+    pub fn typecheck(
+        &mut self,
+        meta: &mut ParserMetadata,
+        unused_else: &str,
+    ) -> Result<Option<Vec<String>>, Failure> {
+        self.subject.typecheck(meta)?;
+        let subject_type = self.subject.get_type();
+        let subject_var = match &self.subject.value {
+            Some(ExprType::VariableGet(var)) => Some(var.name.clone()),
+            _ => None,
+        };
+        let mut handled = HashSet::new();
+        let mut remaining_type = Some(subject_type.clone());
+        let mut accumulated_neg_facts = HashMap::new();
+        // Arm with a case that always matches, it replaces the 'else' arm
+        let mut always_matched: Option<Box<T>> = None;
+
+        for (cases, mut arm) in std::mem::take(&mut self.arms) {
+            if always_matched.is_some() {
+                if let Some(case) = cases.first() {
+                    let pos = case.get_position(meta);
+                    warn_dead_code(
+                        meta,
+                        pos,
+                        "Case is unreachable, previous case always matches",
+                    );
+                }
+                continue;
+            }
+            let is_single_case = cases.len() == 1;
+            let mut arm_facts = accumulated_neg_facts.clone();
+            let mut runtime_cases = vec![];
+            let mut matches_always = false;
+            for mut case in cases {
+                match case.typecheck(meta, &self.subject, &mut handled)? {
+                    Some(result) => matches_always |= result,
+                    None => runtime_cases.push(case.clone()),
+                }
+                if let (MatchCase::Type(kind, _), Some(var)) = (&case, &subject_var) {
+                    if is_single_case {
+                        arm_facts.insert(var.clone(), kind.clone());
+                    }
+                    remaining_type = remaining_type.and_then(|remaining| remaining.exclude(kind));
+                }
+            }
+            // Arms whose type tests never match are dropped
+            if !matches_always && runtime_cases.is_empty() {
+                continue;
+            }
+            meta.with_narrowed_scope(arm_facts, |meta| arm.typecheck(meta))?;
+            if let (Some(var), Some(remaining)) = (&subject_var, &remaining_type) {
+                accumulated_neg_facts.insert(var.clone(), remaining.clone());
+            }
+            if matches_always {
+                always_matched = Some(Box::new(arm));
+            } else {
+                self.arms.push((runtime_cases, arm));
+            }
+        }
+
+        let else_arm = self.else_arm.take();
+        if always_matched.is_some() {
+            if else_arm.is_some() {
+                let pos = PositionInfo::from_token(meta, self.else_tok.clone());
+                warn_dead_code(
+                    meta,
+                    pos,
+                    &format!("Previous case always matches, {unused_else}"),
+                );
+            }
+            self.else_arm = always_matched;
+            return Ok(None);
+        }
+
+        let missing = get_missing_cases(&subject_type, &handled);
+        let is_exhaustive = matches!(subject_type, Type::Enum(_)) && missing.is_empty();
+        match else_arm {
+            Some(_) if is_exhaustive => {
+                let pos = PositionInfo::from_token(meta, self.else_tok.clone());
+                warn_dead_code(meta, pos, &format!("All cases are handled, {unused_else}"));
+                Ok(None)
+            }
+            Some(mut else_arm) => {
+                meta.with_narrowed_scope(accumulated_neg_facts, |meta| else_arm.typecheck(meta))?;
+                self.else_arm = Some(else_arm);
+                Ok(None)
+            }
+            None if is_exhaustive => Ok(None),
+            None => Ok(Some(missing)),
+        }
+    }
+
+    pub fn get_arms(&self) -> impl Iterator<Item = &T> {
+        self.arms
+            .iter()
+            .map(|(_, arm)| arm)
+            .chain(self.else_arm.as_deref())
+    }
+
+    /// Ranges cannot be expressed as `case` patterns, so matches that
+    /// contain them are translated to a chain of conditions instead
+    pub fn has_ranges(&self) -> bool {
+        self.arms
+            .iter()
+            .flat_map(|(cases, _)| cases)
+            .any(|case| matches!(case, MatchCase::Range(_)))
+    }
+
+    /// Translates the cases of every arm to a `case` pattern
+    pub fn translate_patterns(&self, meta: &mut TranslateMetadata) -> Vec<FragmentKind> {
+        self.arms
+            .iter()
+            .map(|(cases, _)| {
+                let mut patterns = vec![];
+                for (index, case) in cases.iter().enumerate() {
+                    if index > 0 {
+                        patterns.push(fragments!("|"));
+                    }
+                    match case {
+                        MatchCase::Value(expr) => patterns.push(expr.translate(meta)),
+                        _ => unreachable!("Only values can be translated to patterns"),
+                    }
+                }
+                ListFragment::new(patterns).to_frag()
+            })
+            .collect()
+    }
+
+    /// Translates the cases of every arm to an arithmetic condition on the subject
+    pub fn translate_conditions(&self, meta: &mut TranslateMetadata) -> Vec<FragmentKind> {
+        // The subject is evaluated once and then compared with every case
+        let subject = match self.subject.translate(meta) {
+            FragmentKind::VarExpr(var) => var.to_frag(),
+            value => {
+                let id = meta.gen_value_id();
+                let var_stmt = VarStmtFragment::new("match", Type::Int, value).with_global_id(id);
+                meta.push_ephemeral_variable(var_stmt).to_frag()
+            }
+        };
+        self.arms
+            .iter()
+            .map(|(cases, _)| {
+                cases
+                    .iter()
+                    .map(|case| match case {
+                        MatchCase::Value(expr) => {
+                            let value = expr.translate(meta);
+                            ArithmeticFragment::new(subject.clone(), ArithOp::Eq, value).to_frag()
+                        }
+                        MatchCase::Range(expr) => {
+                            let Some(ExprType::Range(range)) = &expr.value else {
+                                unreachable!("Range case has to contain a range")
+                            };
+                            let from = range.from.translate(meta);
+                            let to = range.to.translate(meta);
+                            let to_op = if range.neq { ArithOp::Lt } else { ArithOp::Le };
+                            let lower = ArithmeticFragment::new(subject.clone(), ArithOp::Ge, from);
+                            let upper = ArithmeticFragment::new(subject.clone(), to_op, to);
+                            ArithmeticFragment::new(lower.to_frag(), ArithOp::And, upper.to_frag())
+                                .to_frag()
+                        }
+                        MatchCase::Type(..) => {
+                            unreachable!("Type cases are resolved at compile time")
+                        }
+                    })
+                    .reduce(|acc, condition| {
+                        ArithmeticFragment::new(acc, ArithOp::Or, condition).to_frag()
+                    })
+                    .expect("Match arm has to have at least one case")
+            })
+            .collect()
+    }
+}
+
+/// Parses the comma separated cases of a single match arm
+fn parse_match_cases(meta: &mut ParserMetadata) -> Result<Vec<MatchCase>, Failure> {
+    let mut cases = vec![];
+    loop {
+        cases.push(MatchCase::parse(meta)?);
+        if token(meta, ",").is_err() {
+            return Ok(cases);
+        }
+    }
+}
+
+/// Skips new lines and comments between the arms of a match
+fn skip_match_separators(meta: &mut ParserMetadata) {
+    while token_by(meta, |token| {
+        token.starts_with("//") || token.starts_with('\n')
+    })
+    .is_ok()
+    {}
+}
+
+/// Returns the variants of the enum that are not handled by any case
+fn get_missing_cases(subject_type: &Type, handled: &HashSet<String>) -> Vec<String> {
+    match subject_type {
+        Type::Enum(enumeration) => enumeration
+            .variants
+            .iter()
+            .map(|variant| format!("{}.{variant}", enumeration.name))
+            .filter(|case| !handled.contains(case))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Renders the list of missing cases for error messages
+pub fn render_missing_cases(missing: &[String]) -> String {
+    missing.iter().map(|case| format!("'{case}'")).join(", ")
+}
+
+/// Renders the condition of an `if` or `elif` branch
+pub fn translate_condition(keyword: &str, condition: FragmentKind) -> FragmentKind {
+    fragments!(raw_fragment!("{keyword} [ "), condition, " != 0 ]; then")
+}
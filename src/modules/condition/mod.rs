@@ -1,3 +1,24 @@
+use crate::utils::cc_flags::{get_ccflag_name, CCFlags};
+use crate::utils::ParserMetadata;
+use heraclitus_compiler::prelude::*;
+
 pub mod failure_handler;
 pub mod ifchain;
 pub mod ifcond;
+pub mod match_stmt;
+pub mod match_utils;
+pub mod try_stmt;
+
+/// Warns about code that will never execute unless the dead code is explicitly allowed
+pub fn warn_dead_code(meta: &mut ParserMetadata, pos: PositionInfo, reason: &str) {
+    if meta.context.cc_flags.contains(&CCFlags::AllowDeadCode) {
+        return;
+    }
+    let flag_name = get_ccflag_name(CCFlags::AllowDeadCode);
+    let message = Message::new_warn_at_position(meta, pos)
+        .message(reason)
+        .comment(format!(
+            "To suppress this warning, use '{flag_name}' compiler flag"
+        ));
+    meta.add_message(message);
+}
@@ -0,0 +1,116 @@
+use crate::modules::block::Block;
+use crate::modules::condition::failure_handler::FailureHandler;
+use crate::modules::function::fail::FAIL_MESSAGE;
+use crate::modules::prelude::*;
+use crate::modules::types::Type;
+use crate::utils::context::Context;
+use crate::utils::metadata::ShellLoop;
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Status code of the failure that left the body of a try block
+const TRY_STATUS: &str = "__try_status";
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "try"]
+pub struct Try {
+    block: Block,
+    failure_handler: FailureHandler,
+}
+
+impl SyntaxModule<ParserMetadata> for Try {
+    syntax_name!("Try");
+
+    fn new() -> Self {
+        Try {
+            block: Block::new().with_needs_noop().with_condition(),
+            failure_handler: FailureHandler::new(),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "try")?;
+        // Failures that are not handled in the body are handled by the try block
+        meta.with_context_fn(Context::set_is_try_ctx, true, |meta| {
+            meta.with_context_fn(Context::set_is_trust_ctx, false, |meta| {
+                syntax(meta, &mut self.block)
+            })
+        })?;
+        let tok = meta.get_current_token();
+        let word = tok.as_ref().map(|tok| tok.word.as_str());
+        if !matches!(word, Some("?" | "failed" | "succeeded" | "exited")) {
+            return error!(meta, tok => {
+                message: "Try block must be followed by a failure handler",
+                comment: "You can use '?' to propagate failure, 'failed' block to handle failure, 'succeeded' block to handle success, 'exited' block to handle both"
+            });
+        }
+        syntax(meta, &mut self.failure_handler)?;
+        self.failure_handler.set_try_handler();
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Try {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.block.typecheck(meta)?;
+        self.failure_handler.typecheck(meta)
+    }
+}
+
+impl TranslateModule for Try {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // The body runs in a loop that is left on the first failure
+        let body = meta.with_loop(ShellLoop::Try, |meta| self.block.translate(meta));
+        let success = VarStmtFragment::new(TRY_STATUS, Type::Int, raw_fragment!("0"))
+            .with_optimization_when_unused(false);
+        let status = VarExprFragment::new(TRY_STATUS, Type::Int).to_frag();
+        BlockFragment::new(
+            vec![
+                fragments!("while :; do"),
+                body,
+                BlockFragment::new(vec![success.to_frag(), fragments!("break")], true).to_frag(),
+                fragments!("done"),
+                self.failure_handler.translate_status(meta, status),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+}
+
+/// Leaves the body of the innermost try block with given status code.
+/// The message of a failure propagated from a function call is kept, otherwise it is cleared.
+pub fn translate_try_jump(
+    meta: &TranslateMetadata,
+    status: FragmentKind,
+    is_propagated: bool,
+) -> FragmentKind {
+    // Failures in the body of a parallel loop or a block with a timeout end its job
+    let innermost = meta
+        .loops
+        .iter()
+        .rfind(|shell_loop| matches!(shell_loop, ShellLoop::Try | ShellLoop::Job));
+    if innermost == Some(&ShellLoop::Job) {
+        return BlockFragment::new(vec![fragments!("exit ", status)], true).to_frag();
+    }
+    let depth = meta
+        .loop_depth(ShellLoop::Try)
+        .expect("Failure is not inside of a try block");
+    let message = if is_propagated {
+        FragmentKind::Empty
+    } else {
+        VarStmtFragment::new(FAIL_MESSAGE, Type::Text, fragments!("''"))
+            .with_optimization_when_unused(false)
+            .to_frag()
+    };
+    let save =
+        VarStmtFragment::new(TRY_STATUS, Type::Int, status).with_optimization_when_unused(false);
+    let jump = match depth {
+        1 => fragments!("break"),
+        depth => raw_fragment!("break {depth}"),
+    };
+    BlockFragment::new(vec![message, save.to_frag(), jump], true).to_frag()
+}
+
+crate::impl_documentation_noop!(Try);
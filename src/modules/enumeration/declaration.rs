@@ -0,0 +1,182 @@
+use crate::modules::function::declaration_utils::is_declaration_comment_doc;
+use crate::modules::prelude::*;
+use crate::modules::statement::comment_doc::CommentDoc;
+use crate::modules::structure::is_on_new_line;
+use crate::modules::types::{is_type_name, EnumType, Type};
+use crate::modules::variable::variable_name_extensions;
+use crate::utils::context::TypeDecl;
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "enum"]
+#[kind = "stmt"]
+pub struct EnumDeclaration {
+    pub name: String,
+    pub variants: Vec<(String, Option<Token>)>,
+    pub is_public: bool,
+    pub comment: Option<CommentDoc>,
+    /// Token for enum name (for error positioning)
+    pub name_token: Option<Token>,
+}
+
+impl EnumDeclaration {
+    fn render_signature(&self) -> String {
+        let public = if self.is_public { "pub " } else { "" };
+        let variants = self
+            .variants
+            .iter()
+            .map(|(name, _)| format!("    {name}"))
+            .join(",\n");
+        format!("{public}enum {} {{\n{variants}\n}}", self.name)
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for EnumDeclaration {
+    syntax_name!("Enum Declaration");
+
+    fn new() -> Self {
+        EnumDeclaration {
+            name: String::new(),
+            variants: vec![],
+            is_public: false,
+            comment: None,
+            name_token: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Parse the enum comment
+        if is_declaration_comment_doc(meta, "enum") {
+            let mut comment = CommentDoc::new();
+            syntax(meta, &mut comment)?;
+            self.comment = Some(comment);
+        }
+        self.is_public = token(meta, "pub").is_ok();
+        token(meta, "enum")?;
+        self.name_token = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        context!(
+            {
+                token(meta, "{")?;
+                loop {
+                    // Skip comments and newlines
+                    if token_by(meta, |token| {
+                        token.starts_with("//") || token.starts_with('\n')
+                    })
+                    .is_ok()
+                    {
+                        continue;
+                    }
+                    if token(meta, "}").is_ok() {
+                        break;
+                    }
+                    let tok = meta.get_current_token();
+                    let name = variable(meta, variable_name_extensions())?;
+                    self.variants.push((name, tok));
+                    if token(meta, "}").is_ok() {
+                        break;
+                    }
+                    // Variants are separated either by a comma or by a new line
+                    if token(meta, ",").is_err() && !is_on_new_line(meta) {
+                        return error!(
+                            meta,
+                            meta.get_current_token(),
+                            "Expected ',' or '}' after enum variant"
+                        );
+                    }
+                }
+                Ok(())
+            },
+            |pos| {
+                error_pos!(
+                    meta,
+                    pos,
+                    format!("Failed to parse enum declaration '{}'", self.name)
+                )
+            }
+        )
+    }
+}
+
+impl TypeCheckModule for EnumDeclaration {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if !meta.is_global_scope() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                "Enums can only be declared in the global scope"
+            );
+        }
+        if !is_type_name(&self.name) {
+            return error!(meta, self.name_token.clone() => {
+                message: format!("Enum name '{}' is not in pascal case", self.name),
+                comment: "Enum names have to start with an uppercase letter, for example 'LogLevel'"
+            });
+        }
+        if meta.get_type_declaration(&self.name).is_some() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                format!("Type '{}' is already defined", self.name)
+            );
+        }
+        if self.variants.is_empty() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                format!("Enum '{}' must have at least one variant", self.name)
+            );
+        }
+
+        let mut seen_variants = HashSet::new();
+        for (name, tok) in &self.variants {
+            if !is_type_name(name) {
+                return error!(meta, tok.clone() => {
+                    message: format!("Enum variant '{name}' is not in pascal case"),
+                    comment: "Enum variants have to start with an uppercase letter, for example 'Debug'"
+                });
+            }
+            if !seen_variants.insert(name.clone()) {
+                return error!(
+                    meta,
+                    tok.clone(),
+                    format!("Variant '{name}' is already defined")
+                );
+            }
+        }
+
+        let kind = Type::Enum(EnumType {
+            name: self.name.clone(),
+            variants: self.variants.iter().map(|(name, _)| name.clone()).collect(),
+        });
+        meta.add_type_declaration(TypeDecl {
+            name: self.name.clone(),
+            kind,
+            is_public: self.is_public,
+        });
+        Ok(())
+    }
+}
+
+impl TranslateModule for EnumDeclaration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        FragmentKind::Empty
+    }
+}
+
+impl DocumentationModule for EnumDeclaration {
+    fn document(&self, meta: &ParserMetadata) -> String {
+        let mut result = vec![];
+        result.push(format!("## `{}`\n", self.name));
+        result.push("```ab".to_string());
+        result.push(self.render_signature());
+        result.push("```\n".to_string());
+        if let Some(comment) = &self.comment {
+            result.push(comment.document(meta));
+        }
+        result.push("".to_string());
+        result.join("\n")
+    }
+}
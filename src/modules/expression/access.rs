@@ -3,8 +3,11 @@ use crate::modules::prelude::*;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::{validate_index_accessor, variable_name_extensions};
+use crate::translate::fragments::var_expr::{format_position, VarIndexValue};
 use crate::translate::fragments::var_stmt::VarStmtFragment;
+use crate::translate::map::get_map_variable;
 use crate::translate::module::TranslateModule;
+use crate::translate::packed::{get_packed_element, unpack_value};
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use heraclitus_compiler::prelude::*;
 
@@ -12,15 +15,29 @@ use heraclitus_compiler::prelude::*;
 pub struct Access {
     pub left: Box<Expr>,
     pub index: Box<Option<Expr>>,
+    pub field: Option<String>,
+    pub field_tok: Option<Token>,
     pub kind: Type,
 }
 
 impl Typed for Access {
     fn get_type(&self) -> Type {
+        if let (Some(field), Type::Struct(structure)) = (&self.field, &self.kind) {
+            return structure
+                .get_field(field)
+                .map(|(_, kind)| kind.clone())
+                .unwrap_or(Type::Null);
+        }
         if let Some(index) = self.index.as_ref() {
             match (&index.value, &self.kind) {
                 (Some(ExprType::Range(_)), _) => self.kind.clone(),
                 (Some(_), Type::Array(item_type)) => *item_type.clone(),
+                (Some(_), Type::Map(value_type)) => *value_type.clone(),
+                (Some(_), Type::Tuple(types)) => index
+                    .get_integer_value()
+                    .and_then(|index| usize::try_from(index).ok())
+                    .and_then(|index| types.get(index).cloned())
+                    .unwrap_or(Type::Null),
                 _ => self.kind.clone(),
             }
         } else {
@@ -34,6 +51,8 @@ impl Default for Access {
         Access {
             left: Box::new(Expr::new()),
             index: Box::new(None),
+            field: None,
+            field_tok: None,
             kind: Type::Null,
         }
     }
@@ -44,6 +63,16 @@ impl Access {
         Access::default()
     }
 
+    fn is_range(&self) -> bool {
+        matches!(
+            self.index.as_ref(),
+            Some(Expr {
+                value: Some(ExprType::Range(_)),
+                ..
+            })
+        )
+    }
+
     pub fn set_left(&mut self, left: Expr) {
         *self.left = left;
     }
@@ -56,10 +85,23 @@ impl Access {
                 meta.get_current_token(),
             )));
         }
+        if self.is_field_access(meta) {
+            token(meta, ".")?;
+            self.field_tok = meta.get_current_token();
+            self.field = Some(variable(meta, variable_name_extensions())?);
+            return Ok(());
+        }
         token(meta, "[")?;
         Ok(())
     }
 
+    fn is_field_access(&self, meta: &mut ParserMetadata) -> bool {
+        let index = meta.get_index();
+        let result = token(meta, ".").is_ok() && variable(meta, variable_name_extensions()).is_ok();
+        meta.set_index(index);
+        result
+    }
+
     fn is_destruct(&self, meta: &mut ParserMetadata) -> bool {
         let index = meta.get_index();
         if token(meta, "[").is_err() {
@@ -97,6 +139,10 @@ impl SyntaxModule<ParserMetadata> for Access {
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Field name was already consumed with the operator
+        if self.field.is_some() {
+            return Ok(());
+        }
         let mut index = Expr::new();
         syntax(meta, &mut index)?;
         token(meta, "]")?;
@@ -110,9 +156,57 @@ impl TypeCheckModule for Access {
         self.left.typecheck(meta)?;
         self.kind = self.left.get_type();
 
+        if let Some(field) = &self.field {
+            let Type::Struct(structure) = &self.kind else {
+                return error!(
+                    meta,
+                    self.field_tok.clone(),
+                    format!(
+                        "Cannot access field '{field}' of a non-struct expression of type '{}'",
+                        self.kind
+                    )
+                );
+            };
+            if structure.get_field(field).is_none() {
+                return error!(
+                    meta,
+                    self.field_tok.clone(),
+                    format!("Struct '{}' has no field '{field}'", structure.name)
+                );
+            }
+            meta.packed_used = true;
+            return Ok(());
+        }
+
         if let Some(ref mut index_expr) = self.index.as_mut() {
             let pos = self.left.get_position();
-            if !self.kind.is_allowed_in(&Type::array_of(Type::Generic)) {
+            if self.kind.is_map() {
+                index_expr.typecheck(meta)?;
+                if index_expr.get_type() != Type::Text {
+                    let pos = index_expr.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Map keys have to be of type 'Text'",
+                        comment: format!("Given key is of type '{}'", index_expr.get_type())
+                    });
+                }
+                return Ok(());
+            }
+            if let Type::Tuple(types) = &self.kind {
+                index_expr.typecheck(meta)?;
+                let len = types.len();
+                let index = index_expr.get_integer_value();
+                if !index.is_some_and(|index| (0..len as isize).contains(&index)) {
+                    let pos = index_expr.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Tuple index has to be an integer literal within its bounds",
+                        comment: format!("Tuple of type '{}' has {len} elements", self.kind)
+                    });
+                }
+                meta.packed_used = true;
+                return Ok(());
+            }
+            let is_text = self.kind == Type::Text;
+            if !is_text && !self.kind.is_allowed_in(&Type::array_of(Type::Generic)) {
                 return error_pos!(
                     meta,
                     pos,
@@ -123,8 +217,19 @@ impl TypeCheckModule for Access {
                 );
             }
 
-            index_expr.typecheck(meta)?;
+            // Slices can omit their upper bound
+            match &mut index_expr.value {
+                Some(ExprType::Range(range)) => range.typecheck_slice(meta)?,
+                _ => index_expr.typecheck(meta)?,
+            }
             validate_index_accessor(meta, index_expr, true, pos)?;
+            if is_text {
+                return Ok(());
+            }
+            // Elements of nested arrays are unpacked when accessed
+            if self.get_type().is_array() && !self.is_range() {
+                meta.packed_used = true;
+            }
         }
 
         Ok(())
@@ -134,22 +239,43 @@ impl TypeCheckModule for Access {
 impl TranslateModule for Access {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let left_frag = self.left.translate(meta);
-        match left_frag {
-            FragmentKind::VarExpr(mut var) => {
-                var.kind = self.get_type();
-                var.with_index_by_expr(meta, *self.index.clone()).to_frag()
-            }
+        if let (Some(field), Type::Struct(structure)) = (&self.field, &self.kind) {
+            let (index, kind) = structure.get_field(field).unwrap();
+            let value = get_packed_element(meta, left_frag, index, self.kind.packed_depth());
+            return unpack_value(meta, value, kind);
+        }
+        if let (Type::Map(_), Some(key)) = (&self.kind, self.index.as_ref()) {
+            let mut var = get_map_variable(meta, left_frag, &self.kind)
+                .with_index_by_value(VarIndexValue::Key(key.translate(meta)))
+                .with_index_pos(format_position(key.position.as_ref()));
+            var.kind = self.get_type();
+            return var.to_frag();
+        }
+        if let (Type::Tuple(types), Some(index)) = (&self.kind, self.index.as_ref()) {
+            let index = index.get_integer_value().unwrap() as usize;
+            let value = get_packed_element(meta, left_frag, index, self.kind.packed_depth());
+            return unpack_value(meta, value, &types[index]);
+        }
+        let var = match left_frag {
+            FragmentKind::VarExpr(var) if var.index.is_none() => var,
             _ => {
                 let id = meta.gen_value_id();
                 let name = format!("access_{id}");
                 let stmt = VarStmtFragment::new(&name, self.left.get_type(), left_frag)
                     .with_ephemeral(true);
                 meta.stmt_queue.push_back(stmt.clone().to_frag());
-                let mut var = VarExprFragment::from_stmt(&stmt);
-                var.kind = self.get_type();
-                var.with_index_by_expr(meta, *self.index.clone()).to_frag()
+                VarExprFragment::from_stmt(&stmt)
             }
+        };
+        let kind = self.get_type();
+        // Elements of nested arrays are stored as packed strings
+        let mut var = var.with_index_by_expr(meta, *self.index.clone());
+        if kind.is_array() && !self.is_range() {
+            var.kind = Type::Text;
+            return unpack_value(meta, var.to_frag(), &kind);
         }
+        var.kind = kind;
+        var.to_frag()
     }
 }
 
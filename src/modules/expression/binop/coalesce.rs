@@ -0,0 +1,152 @@
+use crate::fragments;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use heraclitus_compiler::prelude::*;
+use std::collections::VecDeque;
+
+use super::BinOp;
+
+/// Supplies a default value when the left side is `null` or comes from a failed call.
+#[derive(Debug, Clone)]
+pub struct Coalesce {
+    left: Box<Expr>,
+    right: Box<Expr>,
+    kind: Type,
+}
+
+impl Typed for Coalesce {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl BinOp for Coalesce {
+    fn set_left(&mut self, left: Expr) {
+        *self.left = left;
+    }
+
+    fn set_right(&mut self, right: Expr) {
+        *self.right = right;
+    }
+
+    fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "??")?;
+        Ok(())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Coalesce {
+    syntax_name!("Coalesce");
+
+    fn new() -> Self {
+        Coalesce {
+            left: Box::new(Expr::new()),
+            right: Box::new(Expr::new()),
+            kind: Type::Null,
+        }
+    }
+
+    fn parse(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Coalesce {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.left.typecheck(meta)?;
+        self.right.typecheck(meta)?;
+        let left_type = self.left.get_type();
+        let right_type = self.right.get_type();
+        if !left_type.is_nullable() && !self.left.can_fail() {
+            let pos = self.left.get_position();
+            return error_pos!(meta, pos => {
+                message: format!("Value of type '{left_type}' can neither be null nor fail"),
+                comment: "The '??' operator supplies a default for a nullable value or a result of a failable call"
+            });
+        }
+        self.kind = match left_type.exclude(&Type::Null) {
+            None => right_type,
+            Some(kind) if right_type.is_allowed_in(&kind) => kind,
+            Some(kind) if kind.is_allowed_in(&right_type) => right_type,
+            // A nullable default keeps the result nullable
+            Some(kind)
+                if right_type
+                    .exclude(&Type::Null)
+                    .is_some_and(|right| right.is_allowed_in(&kind)) =>
+            {
+                match kind {
+                    Type::Union(mut types) => {
+                        types.push(Type::Null);
+                        Type::Union(types)
+                    }
+                    kind => Type::Union(vec![kind, Type::Null]),
+                }
+            }
+            Some(kind) => {
+                let pos = self.right.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!("Cannot use value of type '{right_type}' as a default for value of type '{kind}'")
+                );
+            }
+        };
+        Ok(())
+    }
+}
+
+impl TranslateModule for Coalesce {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let left = self.left.translate(meta);
+        let id = meta.gen_value_id();
+        let stmt = VarStmtFragment::new("coalesce", self.kind.clone(), left).with_global_id(id);
+        let var = meta.push_ephemeral_variable(stmt);
+        // The default value is only evaluated when it is needed
+        let mut queue = VecDeque::new();
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        let right = self.right.translate(meta);
+        let mut statements = meta.stmt_queue.drain(..).collect::<Vec<_>>();
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        statements.push(
+            VarStmtFragment::new("coalesce", self.kind.clone(), right)
+                .with_global_id(id)
+                .to_frag(),
+        );
+        let mut conditions = vec![];
+        if self.left.can_fail() {
+            let status = VarExprFragment::new("__status", Type::Int).to_frag();
+            conditions.push(fragments!("[ ", status, " != 0 ]"));
+        }
+        if self.left.get_type().is_nullable() {
+            // Null arrays are empty arrays
+            let cond = if self.kind.is_array() {
+                let len = var.clone().with_length_getter(true).to_frag();
+                fragments!("[ ", len, " -eq 0 ]")
+            } else {
+                fragments!("[ -z ", var.clone().to_frag(), " ]")
+            };
+            conditions.push(cond);
+        }
+        let condition = conditions
+            .into_iter()
+            .reduce(|acc, cond| fragments!(acc, " || ", cond))
+            .unwrap_or_default();
+        meta.stmt_queue.push_back(
+            BlockFragment::new(
+                vec![
+                    fragments!("if ", condition, "; then"),
+                    BlockFragment::new(statements, true)
+                        .with_condition(true)
+                        .to_frag(),
+                    fragments!("fi"),
+                ],
+                false,
+            )
+            .to_frag(),
+        );
+        var.to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(Coalesce);
@@ -0,0 +1,86 @@
+use super::{get_binop_position_info, BinOp};
+use crate::fragments;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::map::get_map_variable;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Contains {
+    left: Box<Expr>,
+    right: Box<Expr>,
+}
+
+impl Typed for Contains {
+    fn get_type(&self) -> Type {
+        Type::Bool
+    }
+}
+
+impl BinOp for Contains {
+    fn set_left(&mut self, left: Expr) {
+        *self.left = left;
+    }
+
+    fn set_right(&mut self, right: Expr) {
+        *self.right = right;
+    }
+
+    fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "in")?;
+        Ok(())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Contains {
+    syntax_name!("Contains");
+
+    fn new() -> Self {
+        Contains {
+            left: Box::new(Expr::new()),
+            right: Box::new(Expr::new()),
+        }
+    }
+
+    fn parse(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Contains {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.left.typecheck(meta)?;
+        self.right.typecheck(meta)?;
+        let left_type = self.left.get_type();
+        let right_type = self.right.get_type();
+        if left_type != Type::Text || !right_type.is_map() {
+            let pos = get_binop_position_info(meta, &self.left, &self.right);
+            let message = Message::new_err_at_position(meta, pos)
+                .message(format!("Cannot check if value of type '{left_type}' is in value of type '{right_type}'"))
+                .comment("Operator 'in' checks if a key of type 'Text' exists in a map");
+            return Err(Failure::Loud(message));
+        }
+        Ok(())
+    }
+}
+
+impl TranslateModule for Contains {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let key = self.left.translate(meta).with_quotes(false);
+        let map = self.right.translate(meta);
+        let name = get_map_variable(meta, map, &self.right.get_type())
+            .with_render_type(VarRenderType::NameOf)
+            .to_frag();
+        SubprocessFragment::new(fragments!(
+            "[ \"${",
+            name,
+            "[",
+            key,
+            "]+set}\" != set ]; echo $?"
+        ))
+        .to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(Contains);
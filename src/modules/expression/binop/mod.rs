@@ -8,6 +8,8 @@ use heraclitus_compiler::prelude::*;
 
 pub mod add;
 pub mod and;
+pub mod coalesce;
+pub mod contains;
 pub mod div;
 pub mod eq;
 pub mod ge;
@@ -26,6 +28,11 @@ pub trait BinOp: SyntaxModule<ParserMetadata> + TypeCheckModule {
     fn set_right(&mut self, right: Expr);
     fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult;
 
+    /// Whether the operator is followed by the right operand
+    fn has_right_operand(&self) -> bool {
+        true
+    }
+
     fn typecheck_allowed_types(
         meta: &mut ParserMetadata,
         operator: &str,
@@ -61,6 +68,12 @@ pub trait BinOp: SyntaxModule<ParserMetadata> + TypeCheckModule {
     ) -> Result<Type, Failure> {
         match (left.get_type(), right.get_type()) {
             (Type::Int, Type::Num) | (Type::Num, Type::Int) => Ok(Type::Num),
+            (Type::Map(_), _) | (_, Type::Map(_)) => {
+                let pos = get_binop_position_info(meta, left, right);
+                let message =
+                    Message::new_err_at_position(meta, pos).message("Maps cannot be compared");
+                Err(Failure::Loud(message))
+            }
             // Array type inference
             (Type::Array(left_inner), Type::Array(right_inner))
                 if *left_inner == Type::Generic || *right_inner == Type::Generic =>
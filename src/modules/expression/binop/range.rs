@@ -15,6 +15,8 @@ pub struct Range {
     pub from: Box<Expr>,
     pub to: Box<Expr>,
     pub neq: bool,
+    /// The upper bound is omitted as in `text[2..]` to slice until the end
+    pub is_open: bool,
 }
 
 impl Typed for Range {
@@ -35,8 +37,13 @@ impl BinOp for Range {
     fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         token(meta, "..")?;
         self.neq = token(meta, "=").is_err();
+        self.is_open = self.neq && meta.get_current_token().is_some_and(|tok| tok.word == "]");
         Ok(())
     }
+
+    fn has_right_operand(&self) -> bool {
+        !self.is_open
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for Range {
@@ -47,6 +54,7 @@ impl SyntaxModule<ParserMetadata> for Range {
             from: Box::new(Expr::new()),
             to: Box::new(Expr::new()),
             neq: false,
+            is_open: false,
         }
     }
 
@@ -57,16 +65,14 @@ impl SyntaxModule<ParserMetadata> for Range {
 
 impl TypeCheckModule for Range {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
-        self.from.typecheck(meta)?;
-        self.to.typecheck(meta)?;
-        Self::typecheck_allowed_types(
-            meta,
-            "range operator",
-            &mut self.from,
-            &mut self.to,
-            &[Type::Int],
-        )?;
-        Ok(())
+        if self.is_open {
+            let pos = self.from.get_position();
+            return error_pos!(meta, pos => {
+                message: "Range without an upper bound can only be used to slice a value",
+                comment: "Use it as an index, for example 'items[2..]'"
+            });
+        }
+        self.typecheck_slice(meta)
     }
 }
 
@@ -230,7 +236,145 @@ impl Range {
         }
     }
 
-    pub fn get_array_index(&self, meta: &mut TranslateMetadata) -> (FragmentKind, FragmentKind) {
+    /// Typechecks the range used as an index, where the upper bound can be omitted
+    pub fn typecheck_slice(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.from.typecheck(meta)?;
+        if self.is_open {
+            let kind = self.from.get_type();
+            if !kind.is_allowed_in(&Type::Int) {
+                let pos = self.from.get_position();
+                return error_pos!(meta, pos => {
+                    message: format!("Cannot perform range operator on value of type '{kind}'"),
+                    comment: "Allowed type is Int."
+                });
+            }
+            return Ok(());
+        }
+        self.to.typecheck(meta)?;
+        Self::typecheck_allowed_types(
+            meta,
+            "range operator",
+            &mut self.from,
+            &mut self.to,
+            &[Type::Int],
+        )?;
+        Ok(())
+    }
+
+    /// Translates the range to the offset and length of a slice of a value with given size.
+    /// Negative bounds of text slices and of ranges without an upper bound are counted
+    /// from the end of the value. Other array slices keep capping them at zero.
+    pub fn get_array_index(
+        &self,
+        meta: &mut TranslateMetadata,
+        size: FragmentKind,
+        is_text: bool,
+    ) -> (FragmentKind, FragmentKind) {
+        if !is_text && !self.is_open {
+            return self.get_capped_array_index(meta);
+        }
+        if let (Some(from), Some(mut to)) =
+            (self.from.get_integer_value(), self.to.get_integer_value())
+        {
+            if from >= 0 && to >= 0 {
+                // Make the upper bound exclusive.
+                if !self.neq {
+                    to += 1;
+                }
+                // Slice length cannot be negative.
+                let length = max(to - from, 0);
+                return (
+                    RawFragment::from(from.to_string()).to_frag(),
+                    RawFragment::from(length.to_string()).to_frag(),
+                );
+            }
+        }
+        let size = size.with_quotes(false);
+        // Count negative bounds from the end
+        let from_end = |value: FragmentKind| {
+            let value = value.with_quotes(false);
+            fragments!(
+                "(",
+                value.clone(),
+                " < 0 ? ",
+                value.clone(),
+                " + ",
+                size.clone(),
+                " : ",
+                value,
+                ")"
+            )
+        };
+        // Make the upper bound exclusive.
+        let upper = if self.is_open {
+            size.clone()
+        } else {
+            let upper_id = meta.gen_value_id();
+            let upper_val = self.to.translate(meta);
+            let upper_var_stmt =
+                VarStmtFragment::new("slice_upper", Type::Int, upper_val).with_global_id(upper_id);
+            let upper_var_expr = meta.push_ephemeral_variable(upper_var_stmt).to_frag();
+            let inclusive = if self.neq { "" } else { " + 1" };
+            let upper_val = fragments!(
+                "$((",
+                from_end(upper_var_expr),
+                raw_fragment!("{inclusive}"),
+                "))"
+            );
+            let upper_var_stmt =
+                VarStmtFragment::new("slice_upper", Type::Int, upper_val).with_global_id(upper_id);
+            meta.push_ephemeral_variable(upper_var_stmt).to_frag()
+        };
+
+        // Cap the lower bound at zero.
+        let offset = {
+            let offset_id = meta.gen_value_id();
+            let offset_val = self.from.translate(meta);
+            let offset_var_stmt = VarStmtFragment::new("slice_offset", Type::Int, offset_val)
+                .with_global_id(offset_id);
+            let offset_var_expr = meta.push_ephemeral_variable(offset_var_stmt).to_frag();
+            let offset_val = fragments!("$((", from_end(offset_var_expr), "))");
+            let offset_var_stmt = VarStmtFragment::new("slice_offset", Type::Int, offset_val)
+                .with_global_id(offset_id);
+            let offset_var_expr = meta.push_ephemeral_variable(offset_var_stmt).to_frag();
+            let offset_cap = fragments!(
+                "$((",
+                offset_var_expr.clone().with_quotes(false),
+                " > 0 ? ",
+                offset_var_expr.with_quotes(false),
+                " : 0))"
+            );
+            let offset_var_stmt = VarStmtFragment::new("slice_offset", Type::Int, offset_cap)
+                .with_global_id(offset_id);
+            meta.push_ephemeral_variable(offset_var_stmt).to_frag()
+        };
+
+        // Cap the slice length at zero.
+        let length = {
+            let length_id = meta.gen_value_id();
+            let length_val =
+                ArithmeticFragment::new(Some(upper), ArithOp::Sub, Some(offset.clone())).to_frag();
+            let length_var_stmt = VarStmtFragment::new("slice_length", Type::Int, length_val)
+                .with_global_id(length_id);
+            let length_var_expr = meta.push_ephemeral_variable(length_var_stmt).to_frag();
+            let length_cap = fragments!(
+                "$((",
+                length_var_expr.clone().with_quotes(false),
+                " > 0 ? ",
+                length_var_expr.with_quotes(false),
+                " : 0))"
+            );
+            let length_var_stmt = VarStmtFragment::new("slice_length", Type::Int, length_cap)
+                .with_global_id(length_id);
+            meta.push_ephemeral_variable(length_var_stmt).to_frag()
+        };
+
+        (offset, length)
+    }
+
+    /// Translates the range to the offset and length of an array slice.
+    /// Negative bounds are capped at zero.
+    fn get_capped_array_index(&self, meta: &mut TranslateMetadata) -> (FragmentKind, FragmentKind) {
         if let Some(from) = self.from.get_integer_value() {
             if let Some(mut to) = self.to.get_integer_value() {
                 // Make the upper bound exclusive.
@@ -1,11 +1,14 @@
 use super::binop::{
-    add::Add, and::And, div::Div, eq::Eq, ge::Ge, gt::Gt, le::Le, lt::Lt, modulo::Modulo, mul::Mul,
-    neq::Neq, or::Or, range::Range, sub::Sub,
+    add::Add, and::And, coalesce::Coalesce, contains::Contains, div::Div, eq::Eq, ge::Ge, gt::Gt,
+    le::Le, lt::Lt, modulo::Modulo, mul::Mul, neq::Neq, or::Or, range::Range, sub::Sub,
 };
+use super::force::Force;
 use super::literal::{
-    array::Array, bool::Bool, integer::Integer, null::Null, number::Number, status::Status,
-    text::Text,
+    array::Array, bool::Bool, duration::Duration, enumeration::EnumVariant, integer::Integer,
+    map::Map, null::Null, number::Number, status::Status, structure::StructLiteral, text::Text,
+    tuple::TupleLiteral,
 };
+use super::match_expr::MatchExpression;
 use super::parentheses::Parentheses;
 use super::ternop::ternary::Ternary;
 use super::typeop::{cast::Cast, is::Is};
@@ -16,13 +19,16 @@ use crate::modules::builtin::{
     lines::LinesInvocation, ls::Ls, nameof::Nameof, pid::Pid, pwd::Pwd, shellname::Shellname,
     shellversion::Shellversion,
 };
+use crate::modules::command::capture::Capture;
 use crate::modules::command::cmd::Command;
+use crate::modules::command::spawn::Spawn;
 use crate::modules::expression::access::Access;
 use crate::modules::expression::binop::BinOp;
 use crate::modules::expression::ternop::TernOp;
 use crate::modules::expression::typeop::TypeOp;
 use crate::modules::expression::unop::UnOp;
 use crate::modules::function::invocation::FunctionInvocation;
+use crate::modules::function::lambda::Lambda;
 use crate::modules::prelude::FragmentKind;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::parse_type;
@@ -40,6 +46,7 @@ use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub enum ExprType {
     Bool(Bool),
+    Duration(Duration),
     Number(Number),
     Integer(Integer),
     Text(Text),
@@ -57,14 +64,20 @@ pub enum ExprType {
     Ge(Ge),
     Lt(Lt),
     Le(Le),
+    Contains(Contains),
     Eq(Eq),
     Neq(Neq),
     Not(Not),
     Ternary(Ternary),
+    MatchExpression(MatchExpression),
     LinesInvocation(LinesInvocation),
     FunctionInvocation(FunctionInvocation),
+    Lambda(Lambda),
     Command(Command),
+    Capture(Capture),
+    Spawn(Spawn),
     Array(Array),
+    Map(Map),
     Range(Range),
     Null(Null),
     Cast(Cast),
@@ -78,6 +91,11 @@ pub enum ExprType {
     Pid(Pid),
     Shellname(Shellname),
     Shellversion(Shellversion),
+    StructLiteral(StructLiteral),
+    EnumVariant(EnumVariant),
+    TupleLiteral(TupleLiteral),
+    Coalesce(Coalesce),
+    Force(Force),
 }
 
 impl ExprType {
@@ -139,6 +157,15 @@ impl Expr {
         Message::new_err_at_position(meta, pos)
     }
 
+    /// Whether the expression is a call whose failure is handled by the following operator
+    pub fn can_fail(&self) -> bool {
+        match &self.value {
+            Some(ExprType::FunctionInvocation(invocation)) => invocation.can_fail(),
+            Some(ExprType::Command(command)) => command.can_fail(),
+            _ => false,
+        }
+    }
+
     pub fn analyze_control_flow(&self) -> Option<bool> {
         self.value
             .as_ref()
@@ -172,24 +199,25 @@ impl SyntaxModule<ParserMetadata> for Expr {
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         *self = parse_expression!(meta, [
             ternary @ TernOp => [ Ternary ],
+            coalesce @ BinOp => [ Coalesce ],
             range @ BinOp => [ Range ],
             or @ BinOp => [ Or ],
             and @ BinOp => [ And ],
             equality @ BinOp => [ Eq, Neq ],
-            relation @ BinOp => [ Gt, Ge, Lt, Le ],
+            relation @ BinOp => [ Gt, Ge, Lt, Le, Contains ],
             addition @ BinOp => [ Add, Sub ],
             multiplication @ BinOp => [ Mul, Div, Modulo ],
             types @ TypeOp => [ Is, Cast ],
             unops @ UnOp => [ Neg, Not, Len ],
-            access @ PostfixOp => [ Access ],
+            access @ PostfixOp => [ Access, Force ],
             literals @ Literal => [
                 // Literals
-                Parentheses, Bool, Number, Integer, Text,
-                Array, Null, Status, Nameof,
+                Parentheses, TupleLiteral, Bool, Duration, Number, Integer, Text,
+                Map, Array, Null, Status, Nameof, MatchExpression,
                 // Builtin invocation
                 LinesInvocation, Pwd, Ls, Pid, Shellname, Shellversion,
                 // Function invocation
-                FunctionInvocation, Command,
+                StructLiteral, EnumVariant, Lambda, Capture, Spawn, FunctionInvocation, Command,
                 // Variable access
                 VariableGet
             ]
@@ -209,19 +237,26 @@ impl TypeCheckModule for Expr {
                 And,
                 Array,
                 Bool,
+                Capture,
                 Cast,
                 Command,
+                Contains,
                 Div,
+                Duration,
+                EnumVariant,
                 Eq,
                 FunctionInvocation,
                 Ge,
                 Gt,
                 Integer,
                 Is,
+                Lambda,
                 Le,
                 Len,
                 LinesInvocation,
                 Lt,
+                Map,
+                MatchExpression,
                 Modulo,
                 Mul,
                 Nameof,
@@ -243,7 +278,12 @@ impl TypeCheckModule for Expr {
                 Ls,
                 Pid,
                 Shellname,
-                Shellversion
+                Shellversion,
+                Spawn,
+                StructLiteral,
+                TupleLiteral,
+                Coalesce,
+                Force
             ]
         );
         Ok(())
@@ -261,19 +301,26 @@ impl TranslateModule for Expr {
                     And,
                     Array,
                     Bool,
+                    Capture,
                     Cast,
                     Command,
+                    Contains,
                     Div,
+                    Duration,
+                    EnumVariant,
                     Eq,
                     FunctionInvocation,
                     Ge,
                     Gt,
                     Integer,
                     Is,
+                    Lambda,
                     Le,
                     Len,
                     LinesInvocation,
                     Lt,
+                    Map,
+                    MatchExpression,
                     Modulo,
                     Mul,
                     Nameof,
@@ -295,7 +342,12 @@ impl TranslateModule for Expr {
                     Ls,
                     Pid,
                     Shellname,
-                    Shellversion
+                    Shellversion,
+                    Spawn,
+                    StructLiteral,
+                    TupleLiteral,
+                    Coalesce,
+                    Force
                 ]
             )
         })
@@ -312,19 +364,26 @@ impl DocumentationModule for Expr {
                 And,
                 Array,
                 Bool,
+                Capture,
                 Cast,
                 Command,
+                Contains,
                 Div,
+                Duration,
+                EnumVariant,
                 Eq,
                 FunctionInvocation,
                 Ge,
                 Gt,
                 Integer,
                 Is,
+                Lambda,
                 Le,
                 Len,
                 LinesInvocation,
                 Lt,
+                Map,
+                MatchExpression,
                 Modulo,
                 Mul,
                 Nameof,
@@ -346,7 +405,12 @@ impl DocumentationModule for Expr {
                 Ls,
                 Pid,
                 Shellname,
-                Shellversion
+                Shellversion,
+                Spawn,
+                StructLiteral,
+                TupleLiteral,
+                Coalesce,
+                Force
             ]
         )
     }
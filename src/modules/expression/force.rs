@@ -0,0 +1,119 @@
+use crate::modules::condition::failure_handler::translate_failure_exit;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+/// Forces a value that can be `null` or come from a failable call.
+/// The enclosing function fails when the value is missing.
+#[derive(Debug, Clone)]
+pub struct Force {
+    expr: Box<Expr>,
+    kind: Type,
+    tok: Option<Token>,
+    is_main: bool,
+}
+
+impl Typed for Force {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl Default for Force {
+    fn default() -> Self {
+        Force {
+            expr: Box::new(Expr::new()),
+            kind: Type::Null,
+            tok: None,
+            is_main: false,
+        }
+    }
+}
+
+impl Force {
+    pub fn new() -> Self {
+        Force::default()
+    }
+
+    pub fn set_left(&mut self, left: Expr) {
+        *self.expr = left;
+    }
+
+    pub fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        token(meta, "!")?;
+        if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
+            return error!(
+                meta,
+                self.tok.clone(),
+                "The '!' operator can only be used in the main block or inside a function body"
+            );
+        }
+        if meta.context.is_defer_ctx {
+            return error!(
+                meta,
+                self.tok.clone(),
+                "The '!' operator cannot be used inside of a defer block"
+            );
+        }
+        self.is_main = meta.context.is_main_ctx;
+        Ok(())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Force {
+    syntax_name!("Force");
+
+    fn new() -> Self {
+        Force::default()
+    }
+
+    fn parse(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Force {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.expr.typecheck(meta)?;
+        let kind = self.expr.get_type();
+        if !kind.is_nullable() && !self.expr.can_fail() {
+            return error!(meta, self.tok.clone() => {
+                message: format!("Value of type '{kind}' can neither be null nor fail"),
+                comment: "The '!' operator can only force a nullable value or a result of a failable call"
+            });
+        }
+        self.kind = kind.exclude(&Type::Null).unwrap_or(Type::Null);
+        Ok(())
+    }
+}
+
+impl TranslateModule for Force {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Failure of the call is already propagated by its failure handler
+        let value = self.expr.translate(meta);
+        if !self.expr.get_type().is_nullable() {
+            return value;
+        }
+        let id = meta.gen_value_id();
+        let stmt = VarStmtFragment::new("forced", self.kind.clone(), value).with_global_id(id);
+        let var = meta.push_ephemeral_variable(stmt);
+        let exit = translate_failure_exit(meta, self.is_main, raw_fragment!("1"), false);
+        meta.stmt_queue.push_back(
+            BlockFragment::new(
+                vec![
+                    fragments!("if [ -z ", var.clone().to_frag(), " ]; then"),
+                    exit,
+                    fragments!("fi"),
+                ],
+                false,
+            )
+            .to_frag(),
+        );
+        var.to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(Force);
@@ -0,0 +1,130 @@
+use crate::modules::types::Type;
+use crate::utils::metadata::ParserMetadata;
+use heraclitus_compiler::prelude::*;
+
+/// Alignment of the value within the width of the format specifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatAlign {
+    Left,
+    Right,
+}
+
+/// Format specifier of an interpolated value, such as `{price:>8.2}`.
+/// It follows the `[align][0][width][.precision][type]` syntax.
+#[derive(Debug, Clone)]
+pub struct FormatSpec {
+    pub spec: String,
+    pub align: Option<FormatAlign>,
+    pub zero_pad: bool,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+    pub kind: Option<char>,
+    pub tok: Option<Token>,
+}
+
+impl FormatSpec {
+    /// Parses the format specifier or returns `None` if it is malformed
+    pub fn parse(spec: &str, tok: Option<Token>) -> Option<Self> {
+        let mut chars = spec.chars().peekable();
+        let align = match chars.peek() {
+            Some('<') => Some(FormatAlign::Left),
+            Some('>') => Some(FormatAlign::Right),
+            _ => None,
+        };
+        if align.is_some() {
+            chars.next();
+        }
+        let zero_pad = chars.next_if_eq(&'0').is_some();
+        let read_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let mut digits = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                digits.push(digit);
+            }
+            digits.parse::<usize>().ok()
+        };
+        let width = read_number(&mut chars);
+        let precision = match chars.next_if_eq(&'.') {
+            Some(_) => Some(read_number(&mut chars)?),
+            None => None,
+        };
+        let kind = chars.next_if(|kind| ['x', 'X', 'o', 'e', 'f'].contains(kind));
+        if chars.next().is_some() || spec.is_empty() {
+            return None;
+        }
+        Some(FormatSpec {
+            spec: spec.to_string(),
+            align,
+            zero_pad,
+            width,
+            precision,
+            kind,
+            tok,
+        })
+    }
+
+    /// Checks that the format specifier can be applied to the value of the given type
+    pub fn typecheck(&self, meta: &ParserMetadata, kind: &Type) -> SyntaxResult {
+        let spec = &self.spec;
+        let invalid = |reason: &str| -> SyntaxResult {
+            error!(meta, self.tok.clone() => {
+                message: format!("Invalid format specifier '{spec}' for value of type '{kind}'"),
+                comment: reason
+            })
+        };
+        if self.zero_pad && self.align == Some(FormatAlign::Left) {
+            return invalid("Zero padding can only be used with right alignment");
+        }
+        match kind {
+            Type::Text if self.zero_pad => invalid("Text can only be padded with spaces"),
+            Type::Text if self.precision.is_some() => {
+                invalid("Precision can only be used with Num values")
+            }
+            Type::Text if self.kind.is_some() => invalid("Text values can only be aligned"),
+            Type::Int if self.precision.is_some() => {
+                invalid("Precision can only be used with Num values")
+            }
+            Type::Int if matches!(self.kind, Some('e' | 'f')) => {
+                invalid("Use the 'x', 'X' or 'o' type to format Int values")
+            }
+            Type::Num if matches!(self.kind, Some('x' | 'X' | 'o')) => {
+                invalid("Only Int values can be formatted as hexadecimal or octal numbers")
+            }
+            Type::Num if self.zero_pad && self.precision.is_none() && self.kind.is_none() => {
+                invalid("Zero padding of Num values requires a precision, for example '08.2'")
+            }
+            Type::Text | Type::Int | Type::Num => Ok(()),
+            _ => invalid("Format specifiers can only be used with Text, Int and Num values"),
+        }
+    }
+
+    /// Returns the `printf` format that renders the value of the given type
+    pub fn to_printf(&self, kind: &Type) -> String {
+        let mut format = String::from("%");
+        if self.align == Some(FormatAlign::Left) {
+            format.push('-');
+        }
+        if self.zero_pad {
+            format.push('0');
+        }
+        if let Some(width) = self.width {
+            format.push_str(&width.to_string());
+        }
+        if let Some(precision) = self.precision {
+            format.push_str(&format!(".{precision}"));
+        }
+        let conversion = match (kind, self.kind) {
+            (_, Some(kind)) => kind,
+            (Type::Int, None) => 'd',
+            // Numbers keep their representation unless the precision is given
+            (Type::Num, None) if self.precision.is_some() => 'f',
+            _ => 's',
+        };
+        format.push(conversion);
+        format
+    }
+
+    /// Whether the format renders a floating point number
+    pub fn is_float(&self, kind: &Type) -> bool {
+        matches!(self.to_printf(kind).chars().last(), Some('e' | 'f'))
+    }
+}
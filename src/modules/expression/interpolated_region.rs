@@ -1,5 +1,7 @@
 use crate::modules::expression::expr::Expr;
+use crate::modules::expression::format_spec::FormatSpec;
 use crate::modules::expression::literal::text::TextPart;
+use crate::rules::{MULTILINE_BEGIN, MULTILINE_DELIMITER, RAW_BEGIN, RAW_MULTILINE_BEGIN};
 use crate::utils::metadata::ParserMetadata;
 use heraclitus_compiler::prelude::*;
 
@@ -71,6 +73,31 @@ fn parse_escaped_string(string: String, region_type: &InterpolatedRegionType) ->
     result
 }
 
+/// Parses the expression of an interpolation and its optional format specifier.
+fn parse_interpolation(meta: &mut ParserMetadata) -> Result<TextPart, Failure> {
+    let mut expr = Expr::new();
+    syntax(meta, &mut expr)?;
+    let tok = meta.get_current_token();
+    if token(meta, ":").is_err() {
+        return Ok(TextPart::Expr(Box::new(expr)));
+    }
+    let mut spec = String::new();
+    while let Some(word) = meta.get_current_token().map(|tok| tok.word) {
+        if word == "}" {
+            break;
+        }
+        spec.push_str(&word);
+        meta.increment_index();
+    }
+    match FormatSpec::parse(&spec, tok.clone()) {
+        Some(spec) => Ok(TextPart::Format(Box::new(expr), spec)),
+        None => error!(meta, tok => {
+            message: format!("Invalid format specifier '{spec}'"),
+            comment: "Format specifiers follow the [<|>][0][width][.precision][x|X|o|e|f] syntax"
+        }),
+    }
+}
+
 fn parse_simple_region(word: &str, interpolated_type: &InterpolatedRegionType) -> Vec<TextPart> {
     let content = &word[1..word.len() - 1];
     vec![TextPart::String(parse_escaped_string(
@@ -99,9 +126,7 @@ fn parse_complex_region(
             "}" => is_interp = false,
             _ => {
                 if is_interp {
-                    let mut expr = Expr::new();
-                    syntax(meta, &mut expr)?;
-                    parts.push(TextPart::Expr(Box::new(expr)));
+                    parts.push(parse_interpolation(meta)?);
                     meta.offset_index(-1);
                 } else {
                     if tok.word.ends_with(letter) && !is_escaped(&tok.word, letter) {
@@ -145,6 +170,143 @@ pub fn parse_interpolated_region(
     parse_complex_region(meta, start, letter, interpolated_type)
 }
 
+/// Parses a raw text literal, in which backslashes and braces are taken literally.
+pub fn parse_raw_region(meta: &mut ParserMetadata) -> Result<Vec<TextPart>, Failure> {
+    let word = token_by(meta, |word| {
+        word.starts_with(RAW_MULTILINE_BEGIN)
+            || word.starts_with(RAW_BEGIN) && word.ends_with('"') && word.len() > RAW_BEGIN.len()
+    })?;
+    match word.strip_prefix(RAW_MULTILINE_BEGIN) {
+        Some(content) => {
+            let content = content.strip_suffix(MULTILINE_DELIMITER).unwrap_or(content);
+            let mut parts = vec![TextPart::String(content.to_string())];
+            strip_indentation(&mut parts);
+            Ok(parts)
+        }
+        None => Ok(vec![TextPart::String(
+            word[RAW_BEGIN.len()..word.len() - 1].to_string(),
+        )]),
+    }
+}
+
+/// Parses a multiline text literal and strips the common indentation of its lines.
+pub fn parse_multiline_region(meta: &mut ParserMetadata) -> Result<Vec<TextPart>, Failure> {
+    let start = token_by(meta, |word| word.starts_with(MULTILINE_BEGIN))?;
+    let content = &start[MULTILINE_BEGIN.len()..];
+    let mut parts = vec![];
+    if let Some(text) = content.strip_suffix(MULTILINE_DELIMITER) {
+        parts.push(TextPart::String(text.to_string()));
+        return Ok(finish_multiline_region(parts));
+    }
+    parts.push(TextPart::String(content.to_string()));
+    let mut is_interp = false;
+
+    while let Some(tok) = meta.get_current_token() {
+        match tok.word.as_str() {
+            "{" => is_interp = true,
+            "}" => is_interp = false,
+            _ => {
+                if is_interp {
+                    parts.push(parse_interpolation(meta)?);
+                    meta.offset_index(-1);
+                } else {
+                    if let Some(text) = tok.word.strip_suffix(MULTILINE_DELIMITER) {
+                        meta.increment_index();
+                        parts.push(TextPart::String(text.to_string()));
+                        return Ok(finish_multiline_region(parts));
+                    }
+                    parts.push(TextPart::String(tok.word.clone()));
+                }
+            }
+        }
+        meta.increment_index();
+    }
+
+    Err(Failure::Quiet(PositionInfo::from_metadata(meta)))
+}
+
+fn finish_multiline_region(mut parts: Vec<TextPart>) -> Vec<TextPart> {
+    strip_indentation(&mut parts);
+    parts
+        .into_iter()
+        .map(|part| match part {
+            TextPart::String(text) => {
+                TextPart::String(parse_escaped_string(text, &InterpolatedRegionType::Text))
+            }
+            part => part,
+        })
+        .collect()
+}
+
+/// Strips the blank lines around the delimiters and the common indentation of the lines.
+/// The first line is kept as it is when it follows the opening delimiter.
+fn strip_indentation(parts: &mut [TextPart]) {
+    let is_indent = |letter: char| letter == ' ' || letter == '\t';
+    let mut starts_at_line = false;
+    if let Some(TextPart::String(text)) = parts.first_mut() {
+        if let Some((first, rest)) = text.split_once('\n') {
+            if first.chars().all(is_indent) {
+                *text = rest.to_string();
+                starts_at_line = true;
+            }
+        }
+    }
+    if let Some(TextPart::String(text)) = parts.last_mut() {
+        if let Some((rest, last)) = text.rsplit_once('\n') {
+            if last.chars().all(is_indent) {
+                *text = rest.to_string();
+            }
+        }
+    }
+    // Measure the common indentation of the lines that are not blank
+    let mut indent = usize::MAX;
+    let mut at_line_start = starts_at_line;
+    for (index, part) in parts.iter().enumerate() {
+        let TextPart::String(text) = part else {
+            at_line_start = false;
+            continue;
+        };
+        let lines: Vec<&str> = text.split('\n').collect();
+        for (line_index, line) in lines.iter().enumerate() {
+            if line_index > 0 || at_line_start {
+                let width = line.chars().take_while(|letter| is_indent(*letter)).count();
+                let is_followed_by_expr = line_index + 1 == lines.len()
+                    && matches!(parts.get(index + 1), Some(TextPart::Expr(_)));
+                if width < line.chars().count() || is_followed_by_expr {
+                    indent = indent.min(width);
+                }
+            }
+        }
+        at_line_start = text.ends_with('\n') || (text.is_empty() && at_line_start);
+    }
+    if indent == usize::MAX {
+        indent = 0;
+    }
+    // Remove the common indentation from each line
+    let mut at_line_start = starts_at_line;
+    for part in parts.iter_mut() {
+        let TextPart::String(text) = part else {
+            at_line_start = false;
+            continue;
+        };
+        let stripped = text
+            .split('\n')
+            .enumerate()
+            .map(|(line_index, line)| {
+                if line_index > 0 || at_line_start {
+                    let width = line.chars().take_while(|letter| is_indent(*letter)).count();
+                    &line[width.min(indent)..]
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+        at_line_start = text.ends_with('\n') || (text.is_empty() && at_line_start);
+        *text = stripped;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +410,21 @@ mod tests {
             r#"\${var}"#
         );
     }
+
+    #[test]
+    fn test_strip_indentation() {
+        let strip = |text: &str| {
+            let mut parts = vec![TextPart::String(text.to_string())];
+            strip_indentation(&mut parts);
+            match &parts[0] {
+                TextPart::String(text) => text.clone(),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(strip("\n    a\n      b\n    "), "a\n  b");
+        assert_eq!(strip("\n    a\n\n    b\n"), "a\n\nb");
+        assert_eq!(strip("a\n    b"), "a\nb");
+        assert_eq!(strip("\n\ta\n\t\tb"), "a\n\tb");
+        assert_eq!(strip(""), "");
+    }
 }
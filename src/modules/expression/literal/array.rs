@@ -1,12 +1,16 @@
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
-use crate::modules::types::{try_parse_type, Type, Typed};
+use crate::modules::types::{resolve_type, try_parse_type, Type, Typed, MAX_PACKED_DEPTH};
+use crate::translate::packed::pack_value;
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone)]
 pub struct Array {
     exprs: Vec<Expr>,
+    /// Whether the value is an array whose elements are spread into this one
+    spreads: Vec<bool>,
     kind: Type,
+    tok: Option<Token>,
 }
 
 impl Typed for Array {
@@ -15,41 +19,86 @@ impl Typed for Array {
     }
 }
 
+impl Array {
+    /// Collects the rest arguments of a function invocation
+    pub fn from_rest(exprs: Vec<Expr>, spreads: Vec<bool>, kind: Type, tok: Option<Token>) -> Self {
+        Array {
+            exprs,
+            spreads,
+            kind,
+            tok,
+        }
+    }
+
+    /// Type of the elements that the value at the index contributes
+    fn element_type(&self, index: usize) -> Type {
+        match self.exprs[index].get_type() {
+            Type::Array(inner) if self.is_spread(index) => *inner,
+            kind => kind,
+        }
+    }
+
+    fn is_spread(&self, index: usize) -> bool {
+        self.spreads.get(index).copied().unwrap_or(false)
+    }
+}
+
+/// Returns the type that both element types fit in. An empty array fits in any array type.
+fn unify_element_types(current: &Type, other: &Type) -> Option<Type> {
+    match (current, other) {
+        (current, other) if current == other => Some(current.clone()),
+        (Type::Array(current), Type::Array(other)) => match (&**current, &**other) {
+            (Type::Generic, _) => Some(Type::Array(other.clone())),
+            (_, Type::Generic) => Some(Type::Array(current.clone())),
+            (current, other) => unify_element_types(current, other).map(Type::array_of),
+        },
+        _ => None,
+    }
+}
+
 impl SyntaxModule<ParserMetadata> for Array {
     syntax_name!("Array");
 
     fn new() -> Self {
         Array {
             exprs: vec![],
+            spreads: vec![],
             kind: Type::Generic,
+            tok: None,
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         token(meta, "[")?;
         let tok = meta.get_current_token();
+        self.tok = tok.clone();
+        let index = meta.get_index();
         if token(meta, "]").is_ok() {
             self.kind = Type::Array(Box::new(Type::Generic));
             return Ok(());
         }
         // Try to parse array type
-        match try_parse_type(meta) {
+        let array_type = try_parse_type(meta).and_then(|kind| {
+            token(meta, "]")?;
+            Ok(kind)
+        });
+        match array_type {
             Ok(kind) => {
-                if matches!(kind, Type::Array(_)) {
-                    return error!(
-                        meta,
-                        tok, "Arrays cannot be nested due to the Bash limitations"
-                    );
+                if kind.is_array() && kind.packed_depth() > MAX_PACKED_DEPTH {
+                    return error!(meta, tok => {
+                        message: "Array is nested too deeply",
+                        comment: format!("Arrays can be nested at most {MAX_PACKED_DEPTH} levels deep")
+                    });
                 }
                 if matches!(kind, Type::Union(_)) {
                     return error!(meta, tok, "Arrays don't support mixed type values");
                 }
                 self.kind = Type::Array(Box::new(kind));
-                token(meta, "]")?;
             }
             Err(Failure::Loud(err)) => return Err(Failure::Loud(err)),
             // Parse the array values
             Err(Failure::Quiet(_)) => {
+                meta.set_index(index);
                 loop {
                     // Skip comments and newlines
                     if token_by(meta, |token| {
@@ -84,17 +133,32 @@ impl SyntaxModule<ParserMetadata> for Array {
 
 impl TypeCheckModule for Array {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.kind = resolve_type(meta, &self.kind, self.tok.clone())?;
         // First type-check all the expressions
-        for expr in &mut self.exprs {
-            expr.typecheck(meta)?;
-            // Handle nested arrays
-            if expr.get_type().is_array() {
+        for index in 0..self.exprs.len() {
+            self.exprs[index].typecheck(meta)?;
+            let expr = &self.exprs[index];
+            if self.is_spread(index) && !expr.get_type().is_array() {
+                let pos = expr.get_position();
+                return error_pos!(meta, pos => {
+                    message: format!("Cannot spread value of type '{}'", expr.get_type()),
+                    comment: "Only arrays can be spread"
+                });
+            }
+            // Nested arrays are stored as packed strings
+            if self.element_type(index).is_array() {
+                if self.element_type(index).packed_depth() > MAX_PACKED_DEPTH {
+                    let pos = expr.get_position();
+                    return error_pos!(meta, pos => {
+                        message: "Array is nested too deeply",
+                        comment: format!("Arrays can be nested at most {MAX_PACKED_DEPTH} levels deep")
+                    });
+                }
+                meta.packed_used = true;
+            }
+            if self.element_type(index).is_map() {
                 let pos = expr.get_position();
-                return error_pos!(
-                    meta,
-                    pos,
-                    "Arrays cannot be nested due to the Bash limitations"
-                );
+                return error_pos!(meta, pos, "Arrays cannot contain maps");
             }
         }
 
@@ -106,14 +170,34 @@ impl TypeCheckModule for Array {
 
         match self.kind {
             Type::Generic => {
-                // Infer type from first element
-                self.kind = Type::Array(Box::new(self.exprs[0].get_type()));
+                // Infer type from the elements. Empty arrays take the type of their siblings
+                let mut element_type = self.element_type(0);
+                for (index, expr) in self.exprs.iter().enumerate().skip(1) {
+                    let expr_type = self.element_type(index);
+                    match unify_element_types(&element_type, &expr_type) {
+                        Some(kind) => element_type = kind,
+                        None => {
+                            let pos = expr.get_position();
+                            return error_pos!(
+                                meta,
+                                pos,
+                                format!(
+                                    "Array elements must have the same type. Expected '{}', found '{}'",
+                                    element_type, expr_type
+                                )
+                            );
+                        }
+                    }
+                }
+                self.kind = Type::Array(Box::new(element_type));
             }
             Type::Array(ref expected_type) => {
                 // Type already specified, validate all elements match
-                for expr in &self.exprs {
-                    let expr_type = expr.get_type();
-                    if expr_type != **expected_type {
+                for (index, expr) in self.exprs.iter().enumerate() {
+                    let expr_type = self.element_type(index);
+                    if unify_element_types(expected_type, &expr_type).as_ref()
+                        != Some(expected_type)
+                    {
                         let pos = expr.get_position();
                         return error_pos!(
                             meta,
@@ -126,24 +210,6 @@ impl TypeCheckModule for Array {
             _ => unimplemented!("Unexpected array type state {0}.", self.kind),
         }
 
-        // Validate all elements have the same type
-        if let Type::Array(ref element_type) = self.kind {
-            for expr in &self.exprs[1..] {
-                let expr_type = expr.get_type();
-                if expr_type != **element_type {
-                    let pos = expr.get_position();
-                    return error_pos!(
-                        meta,
-                        pos,
-                        format!(
-                            "Array elements must have the same type. Expected '{}', found '{}'",
-                            element_type, expr_type
-                        )
-                    );
-                }
-            }
-        }
-
         Ok(())
     }
 }
@@ -151,10 +217,27 @@ impl TypeCheckModule for Array {
 impl TranslateModule for Array {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let id = meta.gen_value_id();
+        let element_kind = match &self.kind {
+            Type::Array(inner) => *inner.clone(),
+            kind => kind.clone(),
+        };
         let args = self
             .exprs
             .iter()
-            .map(|expr| expr.translate_eval(meta, false))
+            .enumerate()
+            .map(|(index, expr)| {
+                let kind = expr.get_type();
+                if self.is_spread(index) {
+                    // Elements of a spread array are already in their stored form
+                    expr.translate(meta)
+                } else if kind.is_array() {
+                    // Empty arrays are packed with the separators of their siblings
+                    let value = expr.translate(meta);
+                    pack_value(meta, value, &element_kind)
+                } else {
+                    expr.translate_eval(meta, false)
+                }
+            })
             .collect::<Vec<FragmentKind>>();
         let args = ListFragment::new(args).with_spaces().to_frag();
         let var_stmt = VarStmtFragment::new("array", self.kind.clone(), args).with_global_id(id);
@@ -0,0 +1,68 @@
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::module::TranslateModule;
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+/// Units of the duration literal and the number of milliseconds in each of them
+const DURATION_UNITS: [(&str, isize); 4] =
+    [("ms", 1), ("s", 1000), ("m", 60_000), ("h", 3_600_000)];
+
+/// Parses a duration literal such as `500ms`, `5s` or `2m` into milliseconds
+fn parse_duration(word: &str) -> Option<isize> {
+    let digits = word.find(|letter: char| !letter.is_ascii_digit())?;
+    let (value, unit) = word.split_at(digits);
+    let (_, scale) = DURATION_UNITS.iter().find(|(name, _)| *name == unit)?;
+    value.parse::<isize>().ok()?.checked_mul(*scale)
+}
+
+/// Converts the duration in milliseconds to the seconds accepted by `sleep`
+pub fn translate_duration_seconds(millis: FragmentKind) -> FragmentKind {
+    SubprocessFragment::new(fragments!(
+        "printf '%d.%03d' $(( ",
+        millis.clone(),
+        " / 1000 )) $(( ",
+        millis,
+        " % 1000 ))"
+    ))
+    .to_frag()
+}
+
+#[derive(Debug, Clone)]
+pub struct Duration {
+    pub millis: isize,
+}
+
+impl Typed for Duration {
+    fn get_type(&self) -> Type {
+        Type::duration()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Duration {
+    syntax_name!("Duration");
+
+    fn new() -> Self {
+        Duration { millis: 0 }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let word = token_by(meta, |word| parse_duration(word).is_some())?;
+        self.millis = parse_duration(&word).unwrap_or_default();
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Duration {
+    fn typecheck(&mut self, _meta: &mut ParserMetadata) -> SyntaxResult {
+        Ok(())
+    }
+}
+
+impl TranslateModule for Duration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        raw_fragment!("{}", self.millis)
+    }
+}
+
+crate::impl_documentation_noop!(Duration);
@@ -0,0 +1,90 @@
+use crate::modules::prelude::*;
+use crate::modules::types::{is_type_name, Type, Typed};
+use crate::modules::variable::variable_name_extensions;
+use crate::raw_fragment;
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub name: String,
+    pub variant: String,
+    name_tok: Option<Token>,
+    variant_tok: Option<Token>,
+    kind: Type,
+}
+
+impl Typed for EnumVariant {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for EnumVariant {
+    syntax_name!("Enum Variant");
+
+    fn new() -> Self {
+        EnumVariant {
+            name: String::new(),
+            variant: String::new(),
+            name_tok: None,
+            variant_tok: None,
+            kind: Type::Generic,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.name_tok = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        if !is_type_name(&self.name) {
+            return Err(Failure::Quiet(PositionInfo::from_token(
+                meta,
+                self.name_tok.clone(),
+            )));
+        }
+        token(meta, ".")?;
+        self.variant_tok = meta.get_current_token();
+        self.variant = variable(meta, variable_name_extensions())?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for EnumVariant {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let enumeration = match meta
+            .get_type_declaration(&self.name)
+            .map(|decl| decl.kind.clone())
+        {
+            Some(Type::Enum(enumeration)) => enumeration,
+            Some(kind) => {
+                return error!(
+                    meta,
+                    self.name_tok.clone(),
+                    format!("Type '{}' is not an enum", kind)
+                )
+            }
+            None => {
+                return error!(
+                    meta,
+                    self.name_tok.clone(),
+                    format!("Type '{}' is not defined", self.name)
+                )
+            }
+        };
+        if !enumeration.has_variant(&self.variant) {
+            return error!(meta, self.variant_tok.clone() => {
+                message: format!("Enum '{}' has no variant '{}'", enumeration.name, self.variant),
+                comment: format!("Available variants are: {}", enumeration.variants.join(", "))
+            });
+        }
+        self.kind = Type::Enum(enumeration);
+        Ok(())
+    }
+}
+
+impl TranslateModule for EnumVariant {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        raw_fragment!("{}", self.variant)
+    }
+}
+
+crate::impl_documentation_noop!(EnumVariant);
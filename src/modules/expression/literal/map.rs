@@ -0,0 +1,148 @@
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{is_map_value_type, resolve_type, try_parse_type, Type, Typed};
+use crate::translate::packed::{pack_value, pack_values};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    entries: Vec<(Expr, Expr)>,
+    kind: Type,
+    tok: Option<Token>,
+}
+
+impl Typed for Map {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Map {
+    syntax_name!("Map");
+
+    fn new() -> Self {
+        Map {
+            entries: vec![],
+            kind: Type::map_of(Type::Generic),
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        let index = meta.get_index();
+        // Empty map with the type of its values `[Text: T]`
+        match try_parse_type(meta) {
+            Ok(kind @ Type::Map(_)) => {
+                self.kind = kind;
+                return Ok(());
+            }
+            Err(Failure::Loud(err)) => return Err(Failure::Loud(err)),
+            _ => meta.set_index(index),
+        }
+        token(meta, "[")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            // Allow trailing comma
+            if !self.entries.is_empty() && token(meta, "]").is_ok() {
+                break;
+            }
+            let mut key = Expr::new();
+            syntax(meta, &mut key)?;
+            let tok = meta.get_current_token();
+            if token(meta, ":").is_err() {
+                // Values without keys are parsed by the array literal
+                if self.entries.is_empty() {
+                    return Err(Failure::Quiet(PositionInfo::from_token(meta, tok)));
+                }
+                return error!(meta, tok, "Expected ':' after map key");
+            }
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            self.entries.push((key, value));
+            let tok = meta.get_current_token();
+            if token(meta, "]").is_ok() {
+                break;
+            }
+            if token(meta, ",").is_err() {
+                return error!(meta, tok, "Expected ',' or ']' after map entry");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Map {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.kind = resolve_type(meta, &self.kind, self.tok.clone())?;
+        meta.use_map(PositionInfo::from_token(meta, self.tok.clone()));
+        for (key, value) in self.entries.iter_mut() {
+            key.typecheck(meta)?;
+            value.typecheck(meta)?;
+            if key.get_type() != Type::Text {
+                let pos = key.get_position();
+                return error_pos!(meta, pos => {
+                    message: "Map keys have to be of type 'Text'",
+                    comment: format!("Given key is of type '{}'", key.get_type())
+                });
+            }
+        }
+
+        let Some((_, first)) = self.entries.first() else {
+            // Empty map keeps the type from its type annotation
+            return Ok(());
+        };
+        let value_type = first.get_type();
+        if !is_map_value_type(&value_type) {
+            let pos = first.get_position();
+            return error_pos!(
+                meta,
+                pos,
+                format!("Map values cannot be of type '{value_type}'")
+            );
+        }
+        for (_, value) in &self.entries[1..] {
+            if value.get_type() != value_type {
+                let pos = value.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!(
+                        "Map values must have the same type. Expected '{}', found '{}'",
+                        value_type,
+                        value.get_type()
+                    )
+                );
+            }
+        }
+        self.kind = Type::map_of(value_type);
+        Ok(())
+    }
+}
+
+impl TranslateModule for Map {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let Type::Map(value_type) = &self.kind else {
+            unreachable!("Map literal has to be of a map type")
+        };
+        let values = self
+            .entries
+            .iter()
+            .flat_map(|(key, value)| {
+                let key = key.translate(meta);
+                let value = value.translate(meta);
+                [key, pack_value(meta, value, value_type)]
+            })
+            .collect();
+        pack_values(values, self.kind.packed_depth())
+    }
+}
+
+crate::impl_documentation_noop!(Map);
@@ -1,7 +1,12 @@
 pub mod array;
 pub mod bool;
+pub mod duration;
+pub mod enumeration;
 pub mod integer;
+pub mod map;
 pub mod null;
 pub mod number;
 pub mod status;
+pub mod structure;
 pub mod text;
+pub mod tuple;
@@ -0,0 +1,175 @@
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::structure::is_on_new_line;
+use crate::modules::types::{is_type_name, Type, Typed};
+use crate::modules::variable::variable_name_extensions;
+use crate::translate::packed::{pack_value, pack_values};
+use heraclitus_compiler::prelude::*;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+pub struct StructLiteral {
+    name: String,
+    name_tok: Option<Token>,
+    fields: Vec<(String, Option<Token>, Expr)>,
+    kind: Type,
+}
+
+impl Typed for StructLiteral {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl StructLiteral {
+    // Distinguishes `Name { field: value }` from a variable followed by a block
+    fn is_struct_literal(meta: &mut ParserMetadata) -> bool {
+        let index = meta.get_index();
+        let result = variable(meta, variable_name_extensions()).is_ok() && token(meta, ":").is_ok();
+        meta.set_index(index);
+        result
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for StructLiteral {
+    syntax_name!("Struct Literal");
+
+    fn new() -> Self {
+        StructLiteral {
+            name: String::new(),
+            name_tok: None,
+            fields: vec![],
+            kind: Type::Generic,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.name_tok = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        if !is_type_name(&self.name) {
+            return Err(Failure::Quiet(PositionInfo::from_token(
+                meta,
+                self.name_tok.clone(),
+            )));
+        }
+        token(meta, "{")?;
+        if !Self::is_struct_literal(meta) {
+            return Err(Failure::Quiet(PositionInfo::from_token(
+                meta,
+                self.name_tok.clone(),
+            )));
+        }
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            let name = variable(meta, variable_name_extensions())?;
+            token(meta, ":")?;
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            self.fields.push((name, tok, value));
+            if token(meta, "}").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            if token(meta, ",").is_err() && !is_on_new_line(meta) {
+                return error!(meta, tok, "Expected ',' or '}' after struct field");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for StructLiteral {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let structure = match meta
+            .get_type_declaration(&self.name)
+            .map(|decl| decl.kind.clone())
+        {
+            Some(Type::Struct(structure)) => structure,
+            Some(kind) => {
+                return error!(
+                    meta,
+                    self.name_tok.clone(),
+                    format!("Type '{}' is not a struct", kind)
+                )
+            }
+            None => {
+                return error!(
+                    meta,
+                    self.name_tok.clone(),
+                    format!("Type '{}' is not defined", self.name)
+                )
+            }
+        };
+
+        let mut seen_fields = HashSet::new();
+        for (name, tok, value) in self.fields.iter_mut() {
+            value.typecheck(meta)?;
+            let Some((_, kind)) = structure.get_field(name) else {
+                return error!(
+                    meta,
+                    tok.clone(),
+                    format!("Struct '{}' has no field '{}'", structure.name, name)
+                );
+            };
+            if !seen_fields.insert(name.clone()) {
+                return error!(meta, tok.clone(), format!("Field '{name}' is already set"));
+            }
+            if !value.get_type().is_allowed_in(kind) {
+                let pos = value.get_position();
+                return error_pos!(meta, pos => {
+                    message: format!("Field '{name}' of struct '{}' expects a value of type '{kind}'", structure.name),
+                    comment: format!("Given value is of type '{}'", value.get_type())
+                });
+            }
+        }
+        if let Some((name, _)) = structure
+            .fields
+            .iter()
+            .find(|(name, _)| !seen_fields.contains(name))
+        {
+            return error!(
+                meta,
+                self.name_tok.clone(),
+                format!("Missing field '{name}' in struct '{}'", structure.name)
+            );
+        }
+
+        // Keep the values in the order in which the fields were declared
+        self.fields
+            .sort_by_key(|(name, _, _)| structure.get_field(name).map(|(index, _)| index));
+        meta.packed_used = true;
+        self.kind = Type::Struct(structure);
+        Ok(())
+    }
+}
+
+impl TranslateModule for StructLiteral {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let Type::Struct(structure) = &self.kind else {
+            unreachable!("Struct literal has to be of a struct type")
+        };
+        let values = self
+            .fields
+            .iter()
+            .zip(structure.fields.iter())
+            .map(|((_, _, value), (_, kind))| {
+                let value = value.translate(meta);
+                pack_value(meta, value, kind)
+            })
+            .collect();
+        pack_values(values, self.kind.packed_depth())
+    }
+}
+
+crate::impl_documentation_noop!(StructLiteral);
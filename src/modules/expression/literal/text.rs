@@ -1,17 +1,21 @@
 use crate::modules::expression::expr::Expr;
+use crate::modules::expression::format_spec::FormatSpec;
 use crate::modules::expression::interpolated_region::{
-    parse_interpolated_region, InterpolatedRegionType,
+    parse_interpolated_region, parse_multiline_region, parse_raw_region, InterpolatedRegionType,
 };
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::rules::{MULTILINE_BEGIN, RAW_BEGIN, RAW_MULTILINE_BEGIN};
 use crate::translate::fragments::interpolable::InterpolablePart;
 use crate::translate::module::TranslateModule;
+use crate::{fragments, raw_fragment};
 use heraclitus_compiler::prelude::*;
 
 #[derive(Debug, Clone)]
 pub enum TextPart {
     String(String),
     Expr(Box<Expr>),
+    Format(Box<Expr>, FormatSpec),
 }
 
 impl TextPart {
@@ -19,6 +23,10 @@ impl TextPart {
         match self {
             TextPart::String(_) => Ok(()),
             TextPart::Expr(expr) => expr.typecheck(meta),
+            TextPart::Format(expr, spec) => {
+                expr.typecheck(meta)?;
+                spec.typecheck(meta, &expr.get_type())
+            }
         }
     }
 
@@ -39,6 +47,21 @@ impl TextPart {
                         InterpolablePart::Interp(frag)
                     }
                 }
+                TextPart::Format(expr, spec) => {
+                    let kind = expr.get_type();
+                    // Use the dot as the decimal separator regardless of the locale
+                    let locale = if spec.is_float(&kind) {
+                        "LC_NUMERIC=C "
+                    } else {
+                        ""
+                    };
+                    let format = raw_fragment!("{locale}printf '{}' ", spec.to_printf(&kind));
+                    let value = expr.translate(meta);
+                    let frag = SubprocessFragment::new(fragments!(format, value))
+                        .with_quotes(false)
+                        .to_frag();
+                    InterpolablePart::Interp(frag)
+                }
             })
             .collect()
     }
@@ -63,7 +86,15 @@ impl SyntaxModule<ParserMetadata> for Text {
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
-        self.parts = parse_interpolated_region(meta, &InterpolatedRegionType::Text)?;
+        self.parts = match meta.get_current_token() {
+            Some(tok) if tok.word.starts_with(MULTILINE_BEGIN) => parse_multiline_region(meta)?,
+            Some(tok)
+                if tok.word.starts_with(RAW_BEGIN) || tok.word.starts_with(RAW_MULTILINE_BEGIN) =>
+            {
+                parse_raw_region(meta)?
+            }
+            _ => parse_interpolated_region(meta, &InterpolatedRegionType::Text)?,
+        };
         Ok(())
     }
 }
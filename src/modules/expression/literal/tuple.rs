@@ -0,0 +1,91 @@
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed, MAX_PACKED_DEPTH};
+use crate::translate::packed::{pack_value, pack_values};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct TupleLiteral {
+    values: Vec<Expr>,
+    kind: Type,
+    tok: Option<Token>,
+}
+
+impl Typed for TupleLiteral {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for TupleLiteral {
+    syntax_name!("Tuple Literal");
+
+    fn new() -> Self {
+        TupleLiteral {
+            values: vec![],
+            kind: Type::Generic,
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        token(meta, "(")?;
+        let mut value = Expr::new();
+        syntax(meta, &mut value)?;
+        self.values.push(value);
+        // Values in parentheses without a comma are not tuples
+        token(meta, ",")?;
+        loop {
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            self.values.push(value);
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            let tok = meta.get_current_token();
+            if token(meta, ",").is_err() {
+                return error!(meta, tok, "Expected ',' or ')' after tuple value");
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for TupleLiteral {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        for value in &mut self.values {
+            value.typecheck(meta)?;
+            if value.get_type().is_map() {
+                let pos = value.get_position();
+                return error_pos!(meta, pos, "Tuples cannot contain maps");
+            }
+        }
+        self.kind = Type::Tuple(self.values.iter().map(Expr::get_type).collect());
+        if self.kind.packed_depth() > MAX_PACKED_DEPTH {
+            return error!(meta, self.tok.clone() => {
+                message: "Tuple is nested too deeply",
+                comment: format!("Values can be nested at most {MAX_PACKED_DEPTH} levels deep")
+            });
+        }
+        meta.packed_used = true;
+        Ok(())
+    }
+}
+
+impl TranslateModule for TupleLiteral {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let values = self
+            .values
+            .iter()
+            .map(|value| {
+                let kind = value.get_type();
+                let value = value.translate(meta);
+                pack_value(meta, value, &kind)
+            })
+            .collect();
+        pack_values(values, self.kind.packed_depth())
+    }
+}
+
+crate::impl_documentation_noop!(TupleLiteral);
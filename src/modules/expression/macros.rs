@@ -9,7 +9,9 @@ macro_rules! parse_expression_group {
                 let mut module = $cur_modules::new();
                 match module.parse_operator($meta) {
                     Ok(()) => {
-                        module.set_right($prev($meta)?);
+                        if module.has_right_operand() {
+                            module.set_right($prev($meta)?);
+                        }
                         module.set_left(node);
                         syntax($meta, &mut module)?;
                         let end_index = $meta.get_index();
@@ -0,0 +1,155 @@
+use crate::modules::condition::match_utils::{render_missing_cases, MatchArms};
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::{fragments, raw_fragment};
+use heraclitus_compiler::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct MatchExpression {
+    arms: MatchArms<Expr>,
+    kind: Type,
+}
+
+impl Typed for MatchExpression {
+    fn get_type(&self) -> Type {
+        self.kind.clone()
+    }
+}
+
+impl MatchExpression {
+    fn translate_case(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let subject = self.arms.subject.translate(meta);
+        let patterns = self.arms.translate_patterns(meta);
+        let mut arms = vec![];
+        // Patterns start with a parenthesis so that older shells do not
+        // confuse their closing parenthesis with the end of the subshell
+        for (pattern, (_, value)) in patterns.into_iter().zip(&self.arms.arms) {
+            let value = value.translate(meta);
+            arms.push(fragments!(" (", pattern, ") echo ", value, ";;"));
+        }
+        if let Some(else_expr) = &self.arms.else_arm {
+            let value = else_expr.translate(meta);
+            arms.push(fragments!(" (*) echo ", value, ";;"));
+        }
+        fragments!(
+            "case ",
+            subject,
+            " in",
+            ListFragment::new(arms).to_frag(),
+            " esac"
+        )
+    }
+
+    fn translate_if_chain(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let conditions = self.arms.translate_conditions(meta);
+        let mut result = vec![];
+        for (index, (condition, (_, value))) in
+            conditions.into_iter().zip(&self.arms.arms).enumerate()
+        {
+            let keyword = if index == 0 { "if" } else { "elif" };
+            let value = value.translate(meta);
+            result.push(fragments!(
+                raw_fragment!("{keyword} [ "),
+                condition,
+                " != 0 ]; then echo ",
+                value,
+                "; "
+            ));
+        }
+        if let Some(else_expr) = &self.arms.else_arm {
+            let value = else_expr.translate(meta);
+            result.push(fragments!("else echo ", value, "; "));
+        }
+        result.push(fragments!("fi"));
+        ListFragment::new(result).to_frag()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for MatchExpression {
+    syntax_name!("Match Expression");
+
+    fn new() -> Self {
+        MatchExpression {
+            arms: MatchArms::new(),
+            kind: Type::Null,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.arms.parse(meta, |meta| {
+            token(meta, ":")?;
+            let mut value = Expr::new();
+            syntax(meta, &mut value)?;
+            let _ = token(meta, ",");
+            Ok(value)
+        })
+    }
+}
+
+impl TypeCheckModule for MatchExpression {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let missing = self
+            .arms
+            .typecheck(meta, "'else' value will never be used")?;
+        // Every case has to produce a value
+        match missing {
+            Some(missing) if !missing.is_empty() => {
+                return error!(meta, self.arms.tok.clone() => {
+                    message: format!("Match expression is not exhaustive, missing {}", render_missing_cases(&missing)),
+                    comment: "Add the missing cases or an 'else' value"
+                });
+            }
+            Some(_) => {
+                return error!(meta, self.arms.tok.clone() => {
+                    message: "Match expression is not exhaustive",
+                    comment: "Add an 'else' value"
+                });
+            }
+            None => {}
+        }
+
+        let types = self.arms.get_arms().map(Expr::get_type).collect::<Vec<_>>();
+        let Some(kind) = types.first().cloned() else {
+            return error!(
+                meta,
+                self.arms.tok.clone(),
+                "Match expression has to have at least one case"
+            );
+        };
+        if let Some((other, other_type)) = self
+            .arms
+            .get_arms()
+            .zip(types)
+            .find(|(_, other_type)| *other_type != kind)
+        {
+            let pos = other.get_position();
+            return error_pos!(meta, pos => {
+                message: "Match expression can only evaluate to value of one type.",
+                comment: format!("Provided cases of type '{kind}' and '{other_type}'.")
+            });
+        }
+        self.kind = kind;
+        Ok(())
+    }
+}
+
+impl TranslateModule for MatchExpression {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let expr = if self.arms.has_ranges() {
+            self.translate_if_chain(meta)
+        } else {
+            self.translate_case(meta)
+        };
+        // Bash cannot parse `case` statements inside of arithmetic expansions,
+        // so the result is always stored in a variable first
+        let value = SubprocessFragment::new(expr)
+            .with_quotes(!self.kind.is_array())
+            .to_frag();
+        let id = meta.gen_value_id();
+        let var_stmt = VarStmtFragment::new("match", self.kind.clone(), value).with_global_id(id);
+        meta.push_ephemeral_variable(var_stmt).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(MatchExpression);
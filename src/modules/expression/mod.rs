@@ -1,9 +1,12 @@
 pub mod access;
 pub mod binop;
 pub mod expr;
+pub mod force;
+pub mod format_spec;
 pub mod interpolated_region;
 pub mod literal;
 pub mod macros;
+pub mod match_expr;
 pub mod parentheses;
 pub mod ternop;
 pub mod typeop;
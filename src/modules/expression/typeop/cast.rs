@@ -1,6 +1,6 @@
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
-use crate::modules::types::{Type, Typed};
+use crate::modules::types::{resolve_type, Type, Typed};
 use crate::utils::cc_flags::{get_ccflag_name, CCFlags};
 use heraclitus_compiler::prelude::*;
 
@@ -10,6 +10,7 @@ use super::TypeOp;
 pub struct Cast {
     expr: Box<Expr>,
     kind: Type,
+    type_tok: Option<Token>,
 }
 
 impl Typed for Cast {
@@ -29,6 +30,7 @@ impl TypeOp for Cast {
 
     fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         token(meta, "as")?;
+        self.type_tok = meta.get_current_token();
         Ok(())
     }
 }
@@ -40,6 +42,7 @@ impl SyntaxModule<ParserMetadata> for Cast {
         Cast {
             expr: Box::new(Expr::new()),
             kind: Type::default(),
+            type_tok: None,
         }
     }
 
@@ -53,6 +56,7 @@ impl TypeCheckModule for Cast {
         self.expr.typecheck(meta)?;
 
         let pos = self.expr.get_position();
+        self.kind = resolve_type(meta, &self.kind, self.type_tok.clone())?;
         if !meta.context.cc_flags.contains(&CCFlags::AllowAbsurdCast) {
             let flag_name = get_ccflag_name(CCFlags::AllowAbsurdCast);
             let l_type = self.expr.get_type();
@@ -65,6 +69,9 @@ impl TypeCheckModule for Cast {
                     "To suppress this warning, use '{flag_name}' compiler flag"
                 ));
             match (l_type, r_type) {
+                // Distinct types are converted from and to their underlying types
+                (Type::Nominal(nominal), other) | (other, Type::Nominal(nominal))
+                    if *nominal.inner == other => {}
                 (Type::Array(left), Type::Array(right))
                     if *left != *right
                         && !matches!(*left, Type::Bool | Type::Num)
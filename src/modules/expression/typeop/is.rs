@@ -1,7 +1,7 @@
 use crate::fragments;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
-use crate::modules::types::{Type, Typed};
+use crate::modules::types::{resolve_type, Type, Typed};
 use heraclitus_compiler::prelude::*;
 
 use super::TypeOp;
@@ -14,6 +14,7 @@ use crate::modules::expression::expr::ExprType;
 pub struct Is {
     expr: Box<Expr>,
     kind: Type,
+    type_tok: Option<Token>,
 }
 
 impl Is {
@@ -67,6 +68,7 @@ impl TypeOp for Is {
 
     fn parse_operator(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         token(meta, "is")?;
+        self.type_tok = meta.get_current_token();
         Ok(())
     }
 }
@@ -78,6 +80,7 @@ impl SyntaxModule<ParserMetadata> for Is {
         Is {
             expr: Box::new(Expr::new()),
             kind: Type::default(),
+            type_tok: None,
         }
     }
 
@@ -88,7 +91,9 @@ impl SyntaxModule<ParserMetadata> for Is {
 
 impl TypeCheckModule for Is {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
-        self.expr.typecheck(meta)
+        self.expr.typecheck(meta)?;
+        self.kind = resolve_type(meta, &self.kind, self.type_tok.clone())?;
+        Ok(())
     }
 }
 
@@ -1,19 +1,18 @@
 use super::declaration_utils::*;
+use super::defer::{translate_defers_epilogue, translate_defers_prologue};
 use crate::fragments;
 use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::statement::comment_doc::CommentDoc;
 use crate::modules::typecheck::TypeCheckModule;
-use crate::modules::types::parse_type;
+use crate::modules::types::{parse_type, resolve_type};
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
 use crate::raw_fragment;
-use crate::translate::fragments::get_variable_name;
 use crate::utils::cc_flags::{get_ccflag_by_name, CCFlags};
 use crate::utils::context::Context;
-use crate::utils::function_cache::FunctionInstance;
 use crate::utils::function_interface::FunctionInterface;
-use crate::utils::function_metadata::FunctionMetadata;
+use crate::utils::function_metadata::{FunctionMetadata, FUNCTION_VALUE_RETURN};
 use crate::utils::ShellType;
 use heraclitus_compiler::prelude::*;
 use itertools::{izip, Itertools};
@@ -31,6 +30,8 @@ pub struct FunctionDeclarationArgument {
     pub kind: Type,
     pub optional: Option<Expr>,
     pub is_ref: bool,
+    /// Whether the argument collects the remaining arguments into an array
+    pub is_variadic: bool,
     pub tok: Option<Token>,
 }
 
@@ -45,6 +46,8 @@ pub struct Ref;
 #[kind = "stmt"]
 pub struct FunctionDeclaration {
     pub name: String,
+    /// Type parameters with their tokens (for error positioning)
+    pub type_params: Vec<(String, Option<Token>)>,
     pub args: Vec<FunctionDeclarationArgument>,
     pub returns: Type,
     pub id: usize,
@@ -59,115 +62,16 @@ pub struct FunctionDeclaration {
     pub is_failable: bool,
     /// Whether function was declared as failable
     pub declared_failable: bool,
+    /// Whether function defers code to run when it returns
+    pub has_defers: bool,
     /// Token for function name (for error positioning)
     pub name_token: Option<Token>,
 }
 
 impl FunctionDeclaration {
-    fn set_args_as_variables(
-        &self,
-        meta: &mut TranslateMetadata,
-        function: &FunctionInstance,
-    ) -> Option<FragmentKind> {
-        if !self.args.is_empty() {
-            let mut result = vec![];
-            for (index, (arg, kind, global_id)) in
-                izip!(self.args.iter(), &function.args, &function.args_global_ids).enumerate()
-            {
-                let name = get_variable_name(&arg.name, *global_id);
-                match (arg.is_ref, kind) {
-                    // Copy array arguments into a function-local array variable.
-                    (false, Type::Array(_)) => {
-                        // ksh cannot copy a caller-local array through indirect expansion, so bind
-                        // the argument name as a nameref first and then copy from that local alias.
-                        if matches!(meta.target.shell, ShellType::Ksh) {
-                            let source_name = format!("{name}_source");
-                            let source_ref = VarStmtFragment::new(
-                                &source_name,
-                                Type::Generic,
-                                VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
-                                    .to_frag(),
-                            )
-                            .with_local(true)
-                            .with_ref(true)
-                            .with_optimization_when_unused(false);
-
-                            let var = VarStmtFragment::new(
-                                &name,
-                                kind.clone(),
-                                VarExprFragment::new(&source_name, kind.clone()).to_frag(),
-                            )
-                            .with_local(true)
-                            .with_optimization_when_unused(false);
-
-                            result.push(source_ref.to_frag());
-                            result.push(var.to_frag());
-                        } else {
-                            let val =
-                                VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
-                                    .with_ref(true)
-                                    .with_declared(false);
-
-                            let var = VarStmtFragment::new(&name, kind.clone(), val.to_frag())
-                                .with_local(true)
-                                .with_optimization_when_unused(false);
-
-                            result.push(var.to_frag())
-                        }
-                    }
-                    // Bind array `ref` arguments so the function can mutate the caller-owned array.
-                    (true, Type::Array(_)) => {
-                        let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
-                            .with_ref(false);
-
-                        let var = if matches!(meta.target.shell, ShellType::Zsh) {
-                            VarStmtFragment::new(&name, kind.clone(), val.to_frag())
-                                .with_local(true)
-                                .with_optimization_when_unused(false)
-                                .with_ref(false)
-                                .with_array_ref(true)
-                                .with_declared(false)
-                        } else if meta.target.shell.is_bash_legacy() {
-                            VarStmtFragment::new(&name, kind.clone(), val.to_frag())
-                                .with_local(true)
-                                .with_optimization_when_unused(false)
-                                .with_ref(true)
-                                .with_array_ref(true)
-                                .with_declared(false)
-                        } else {
-                            VarStmtFragment::new(&name, kind.clone(), val.to_frag())
-                                .with_local(true)
-                                .with_optimization_when_unused(false)
-                                .with_ref(true)
-                                .with_array_ref(true)
-                        };
-
-                        result.push(var.to_frag())
-                    }
-                    // Bind scalar arguments as local variables, preserving `ref` semantics when requested.
-                    _ => {
-                        let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
-                            .with_ref(false);
-
-                        let var = VarStmtFragment::new(&name, kind.clone(), val.to_frag())
-                            .with_local(true)
-                            .with_optimization_when_unused(false)
-                            .with_declared(!arg.is_ref)
-                            .with_ref(arg.is_ref);
-
-                        result.push(var.to_frag())
-                    }
-                }
-                //result.push(var.to_frag());
-            }
-            Some(BlockFragment::new(result, true).to_frag())
-        } else {
-            None
-        }
-    }
-
     fn get_space(&self, parentheses: usize, before: &str, word: &str) -> String {
-        if parentheses == 0 && word == "("
+        if parentheses == 0 && ["(", "<", ">"].contains(&word)
+            || parentheses == 0 && before == "<"
             || word == ":"
             || word == ")"
             || word == "]"
@@ -175,6 +79,7 @@ impl FunctionDeclaration {
             || word == "?"
             || before == "["
             || before == "("
+            || before == "..."
         {
             return String::new();
         }
@@ -215,6 +120,42 @@ impl FunctionDeclaration {
         }
         Ok(result)
     }
+
+    /// Wraps a function variant so that it returns its value in the variable shared by all function values
+    fn translate_value_wrapper(
+        &self,
+        meta: &mut TranslateMetadata,
+        variant_id: usize,
+        returns: &Type,
+    ) -> FragmentKind {
+        let prefix = meta.gen_variable_prefix(&self.name);
+        let name = format!("{prefix}{}__{}_v{variant_id}", self.name, self.id);
+        let ret_name =
+            FunctionMetadata::new(&self.name, self.id, variant_id, returns).mangled_name();
+        // Maps are returned packed into a single string
+        let kind = match returns {
+            Type::Map(_) => Type::Text,
+            kind => kind.clone(),
+        };
+        let value = VarExprFragment::new(&ret_name, kind.clone()).to_frag();
+        let ret = VarStmtFragment::new(FUNCTION_VALUE_RETURN, kind, value)
+            .with_optimization_when_unused(false);
+        let header = if matches!(meta.target.shell, ShellType::Ksh) {
+            raw_fragment!("function {name}__ref {{")
+        } else {
+            raw_fragment!("{name}__ref() {{")
+        };
+        BlockFragment::new(
+            vec![
+                header,
+                BlockFragment::new(vec![raw_fragment!("{name} \"$@\""), ret.to_frag()], true)
+                    .to_frag(),
+                fragments!("}\n"),
+            ],
+            false,
+        )
+        .to_frag()
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
@@ -223,6 +164,7 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
     fn new() -> Self {
         FunctionDeclaration {
             name: String::new(),
+            type_params: vec![],
             args: vec![],
             returns: Type::Generic,
             flags: HashSet::new(),
@@ -233,6 +175,7 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
             function_body: None,
             is_failable: false,
             declared_failable: false,
+            has_defers: false,
             name_token: None,
         }
     }
@@ -262,6 +205,26 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
         let mut optional = false;
         context!(
             {
+                // Get the type parameters
+                if token(meta, "<").is_ok() {
+                    loop {
+                        let tok = meta.get_current_token();
+                        let name = variable(meta, variable_name_extensions())?;
+                        self.type_params.push((name, tok));
+                        if token(meta, ">").is_ok() {
+                            break;
+                        }
+                        token(meta, ",")?;
+                    }
+                }
+                // Type parameters are bound to concrete types on each invocation
+                let type_params = self
+                    .type_params
+                    .iter()
+                    .map(|(name, _)| (name.clone(), Type::Param(name.clone())))
+                    .collect();
+                let prev_type_params =
+                    std::mem::replace(&mut meta.context.type_params, type_params);
                 // Get the arguments
                 token(meta, "(")?;
                 loop {
@@ -277,6 +240,7 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
                         break;
                     }
                     let is_ref = token(meta, "ref").is_ok();
+                    let is_variadic = token(meta, "...").is_ok();
                     let name_token = meta.get_current_token();
                     let name = variable(meta, variable_name_extensions())?;
 
@@ -302,6 +266,7 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
                         kind: arg_type,
                         optional: optional_expr,
                         is_ref,
+                        is_variadic,
                         tok: name_token,
                     });
                     match token(meta, ")") {
@@ -353,11 +318,14 @@ impl SyntaxModule<ParserMetadata> for FunctionDeclaration {
                 let mut block = Block::new().with_condition();
                 let was_fun_ctx = meta.context.is_fun_ctx;
                 meta.context.is_fun_ctx = true;
+                let had_defers = std::mem::replace(&mut meta.context.has_defers, false);
                 let result =
                     meta.with_context_fn(Context::set_cc_flags, self.flags.clone(), |meta| {
                         syntax(meta, &mut block)
                     });
+                self.has_defers = std::mem::replace(&mut meta.context.has_defers, had_defers);
                 meta.context.is_fun_ctx = was_fun_ctx;
+                meta.context.type_params = prev_type_params;
                 result?;
                 self.function_body = Some(block);
 
@@ -390,6 +358,46 @@ impl TypeCheckModule for FunctionDeclaration {
         handle_existing_function(meta, self.name_token.clone())?;
 
         meta.with_context_fn(Context::set_cc_flags, self.flags.clone(), |meta| {
+            // Resolve user defined types in the signature
+            for arg in &mut self.args {
+                arg.kind = resolve_type(meta, &arg.kind, arg.tok.clone())?;
+                if arg.is_ref && arg.kind.is_map() {
+                    return error!(meta, arg.tok.clone() => {
+                        message: "Maps cannot be passed by reference",
+                        comment: "Return the modified map from the function instead"
+                    });
+                }
+            }
+            self.returns = resolve_type(meta, &self.returns, self.name_token.clone())?;
+
+            // Validate the rest argument
+            let last_index = self.args.len().saturating_sub(1);
+            for (index, arg) in self.args.iter().enumerate() {
+                if !arg.is_variadic {
+                    continue;
+                }
+                if index != last_index {
+                    return error!(meta, arg.tok.clone(), "Only the last argument can collect the remaining arguments");
+                }
+                if arg.is_ref {
+                    return error!(meta, arg.tok.clone(), "A rest argument cannot be a ref");
+                }
+                if arg.optional.is_some() {
+                    return error!(meta, arg.tok.clone() => {
+                        message: "A rest argument cannot be optional",
+                        comment: "It is an empty array when no remaining arguments are given"
+                    });
+                }
+                if arg.kind != Type::Generic && !arg.kind.is_array() {
+                    return error!(meta, arg.tok.clone() => {
+                        message: format!("Rest argument '{}' must be an array", arg.name),
+                        comment: format!("Use '...{}: [{}]' to collect the remaining arguments", arg.name, arg.kind)
+                    });
+                }
+            }
+
+            handle_type_params(meta, &self.type_params, &self.args)?;
+
             // Check for duplicate argument names
             let mut seen_argument_names = HashSet::new();
             for arg in &self.args {
@@ -425,7 +433,7 @@ impl TypeCheckModule for FunctionDeclaration {
                     }
 
                     optional_started = true;
-                } else if optional_started {
+                } else if optional_started && !arg.is_variadic {
                     return error!(
                         meta,
                         arg.tok.clone(),
@@ -469,12 +477,10 @@ impl TranslateModule for FunctionDeclaration {
         let prefix = meta.gen_variable_prefix(&self.name);
         // Translate each one of them
         for (index, function) in blocks.iter().enumerate() {
-            meta.fun_meta = Some(FunctionMetadata::new(
-                &self.name,
-                self.id,
-                index,
-                &self.returns,
-            ));
+            meta.fun_meta = Some(
+                FunctionMetadata::new(&self.name, self.id, index, &function.returns)
+                    .with_defers(self.has_defers),
+            );
 
             // Document in code function variant
             let argument_types = izip!(self.args.iter(), function.args.iter())
@@ -491,11 +497,21 @@ impl TranslateModule for FunctionDeclaration {
             } else {
                 result.push(fragments!(name, "() {"));
             }
-            if let Some(args) = self.set_args_as_variables(meta, function) {
+            if let Some(args) = translate_args_as_variables(
+                meta,
+                &self.args,
+                &function.args,
+                &function.args_global_ids,
+            ) {
                 result.push(args);
             }
+            result.push(translate_defers_prologue(meta));
             result.push(function.block.translate(meta));
+            result.push(translate_defers_epilogue(meta));
             result.push(fragments!("}\n"));
+            if function.returns != Type::Null && meta.fun_cache.is_referenced(self.id, index) {
+                result.push(self.translate_value_wrapper(meta, index, &function.returns));
+            }
         }
         // Restore the function name
         meta.fun_meta = prev_fun_meta;
@@ -1,11 +1,15 @@
+use super::declaration::FunctionDeclarationArgument;
 use crate::modules::block::Block;
 use crate::modules::handle_symbol_scope_declaration;
+use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::modules::variable::handle_identifier_name;
+use crate::translate::fragments::get_variable_name;
 use crate::utils::context::Context;
 use crate::utils::function_interface::FunctionInterface;
-use crate::utils::ParserMetadata;
+use crate::utils::ShellType;
 use heraclitus_compiler::prelude::*;
+use itertools::izip;
 
 pub fn skip_function_body(meta: &mut ParserMetadata) -> (usize, usize, bool) {
     let index_begin = meta.get_index();
@@ -17,6 +21,7 @@ pub fn skip_function_body(meta: &mut ParserMetadata) -> (usize, usize, bool) {
             "}" => scope -= 1,
             "fail" => is_failable = true,
             "?" => is_failable = true,
+            "!" => is_failable = true,
             _ => {}
         }
         if scope == 0 {
@@ -29,6 +34,11 @@ pub fn skip_function_body(meta: &mut ParserMetadata) -> (usize, usize, bool) {
 }
 
 pub fn is_functions_comment_doc(meta: &mut ParserMetadata) -> bool {
+    is_declaration_comment_doc(meta, "fun")
+}
+
+/// Checks if the doc comment at the current position belongs to a declaration starting with given keyword
+pub fn is_declaration_comment_doc(meta: &mut ParserMetadata, keyword: &str) -> bool {
     let index = meta.get_index();
     let mut is_comment_doc = true;
     // Multiple linebreaks are merged by heraclitus, so we need to check for them
@@ -59,7 +69,7 @@ pub fn is_functions_comment_doc(meta: &mut ParserMetadata) -> bool {
         if tok.word.starts_with("#[") {
             is_comment_doc = true;
         }
-        if tok.word.starts_with("fun") {
+        if tok.word.starts_with(keyword) {
             meta.set_index(index);
             return true;
         }
@@ -76,6 +86,42 @@ pub fn handle_existing_function(
     handle_symbol_scope_declaration(meta, &name, tok)
 }
 
+pub fn handle_type_params(
+    meta: &mut ParserMetadata,
+    type_params: &[(String, Option<Token>)],
+    args: &[FunctionDeclarationArgument],
+) -> SyntaxResult {
+    for (index, (name, tok)) in type_params.iter().enumerate() {
+        if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return error!(meta, tok.clone() => {
+                message: format!("Type parameter '{name}' has to start with an uppercase letter"),
+                comment: "Type parameters are usually single letters, for example 'T'"
+            });
+        }
+        if type_params[..index].iter().any(|(other, _)| other == name) {
+            return error!(
+                meta,
+                tok.clone(),
+                format!("Type parameter '{name}' is already defined")
+            );
+        }
+        if meta.get_type_declaration(name).is_some() {
+            return error!(
+                meta,
+                tok.clone(),
+                format!("Type parameter '{name}' shadows the type '{name}'")
+            );
+        }
+        if !args.iter().any(|arg| arg.kind.has_param(name)) {
+            return error!(meta, tok.clone() => {
+                message: format!("Type parameter '{name}' is not used by any argument"),
+                comment: "Type parameters are inferred from the arguments of each invocation"
+            });
+        }
+    }
+    Ok(())
+}
+
 pub fn handle_add_function(
     meta: &mut ParserMetadata,
     tok: Option<Token>,
@@ -102,3 +148,104 @@ pub fn handle_add_function(
         None => error!(meta, tok, format!("Function '{}' already exists", name)),
     }
 }
+
+/// Binds the positional arguments of a shell function to the argument variables
+pub fn translate_args_as_variables(
+    meta: &mut TranslateMetadata,
+    args: &[FunctionDeclarationArgument],
+    kinds: &[Type],
+    global_ids: &[Option<usize>],
+) -> Option<FragmentKind> {
+    if !args.is_empty() {
+        let mut result = vec![];
+        for (index, (arg, kind, global_id)) in izip!(args.iter(), kinds, global_ids).enumerate() {
+            let name = get_variable_name(&arg.name, *global_id);
+            match (arg.is_ref, kind) {
+                // Copy array arguments into a function-local array variable.
+                (false, Type::Array(_)) => {
+                    // ksh cannot copy a caller-local array through indirect expansion, so bind
+                    // the argument name as a nameref first and then copy from that local alias.
+                    if matches!(meta.target.shell, ShellType::Ksh) {
+                        let source_name = format!("{name}_source");
+                        let source_ref = VarStmtFragment::new(
+                            &source_name,
+                            Type::Generic,
+                            VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
+                                .to_frag(),
+                        )
+                        .with_local(true)
+                        .with_ref(true)
+                        .with_optimization_when_unused(false);
+
+                        let var = VarStmtFragment::new(
+                            &name,
+                            kind.clone(),
+                            VarExprFragment::new(&source_name, kind.clone()).to_frag(),
+                        )
+                        .with_local(true)
+                        .with_optimization_when_unused(false);
+
+                        result.push(source_ref.to_frag());
+                        result.push(var.to_frag());
+                    } else {
+                        let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
+                            .with_ref(true)
+                            .with_declared(false);
+
+                        let var = VarStmtFragment::new(&name, kind.clone(), val.to_frag())
+                            .with_local(true)
+                            .with_optimization_when_unused(false);
+
+                        result.push(var.to_frag())
+                    }
+                }
+                // Bind array `ref` arguments so the function can mutate the caller-owned array.
+                (true, Type::Array(_)) => {
+                    let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
+                        .with_ref(false);
+
+                    let var = if matches!(meta.target.shell, ShellType::Zsh) {
+                        VarStmtFragment::new(&name, kind.clone(), val.to_frag())
+                            .with_local(true)
+                            .with_optimization_when_unused(false)
+                            .with_ref(false)
+                            .with_array_ref(true)
+                            .with_declared(false)
+                    } else if meta.target.shell.is_bash_legacy() {
+                        VarStmtFragment::new(&name, kind.clone(), val.to_frag())
+                            .with_local(true)
+                            .with_optimization_when_unused(false)
+                            .with_ref(true)
+                            .with_array_ref(true)
+                            .with_declared(false)
+                    } else {
+                        VarStmtFragment::new(&name, kind.clone(), val.to_frag())
+                            .with_local(true)
+                            .with_optimization_when_unused(false)
+                            .with_ref(true)
+                            .with_array_ref(true)
+                    };
+
+                    result.push(var.to_frag())
+                }
+                // Bind scalar arguments as local variables, preserving `ref` semantics when requested.
+                _ => {
+                    let val = VarExprFragment::new(&format!("{}", index + 1), Type::Generic)
+                        .with_ref(false);
+
+                    let var = VarStmtFragment::new(&name, kind.clone(), val.to_frag())
+                        .with_local(true)
+                        .with_optimization_when_unused(false)
+                        .with_declared(!arg.is_ref)
+                        .with_ref(arg.is_ref);
+
+                    result.push(var.to_frag())
+                }
+            }
+            //result.push(var.to_frag());
+        }
+        Some(BlockFragment::new(result, true).to_frag())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,157 @@
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::modules::signal::signal_handled_variable;
+use crate::modules::types::Type;
+use crate::utils::context::Context;
+use crate::utils::function_metadata::FunctionMetadata;
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Deferred code registered in a function, stored as a list of shell commands
+const FUNCTION_DEFERS: &str = "__defers";
+/// Deferred code registered in the main block, run by the exit trap
+const MAIN_DEFERS: &str = "__main_defers";
+/// Status code of the function that is kept while the deferred code runs
+const DEFER_STATUS: &str = "__defer_status";
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "defer"]
+pub struct Defer {
+    pub block: Block,
+    pub is_main: bool,
+}
+
+impl SyntaxModule<ParserMetadata> for Defer {
+    syntax_name!("Defer");
+
+    fn new() -> Self {
+        Defer {
+            block: Block::new().with_needs_noop().with_condition(),
+            is_main: false,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "defer")?;
+        if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
+            return error!(meta, tok => {
+                message: "Defer statement outside of function or main",
+                comment: "Defer statements can only be used inside of functions or the main block"
+            });
+        }
+        if meta.context.is_defer_ctx {
+            return error!(
+                meta,
+                tok, "Defer statement cannot be nested in another defer block"
+            );
+        }
+        self.is_main = meta.context.is_main_ctx && !meta.context.is_fun_ctx;
+        meta.context.has_defers = true;
+        // Deferred code runs after the try block that encloses it has finished
+        meta.with_context_fn(Context::set_is_try_ctx, false, |meta| {
+            meta.with_context_fn(Context::set_is_defer_ctx, true, |meta| {
+                syntax(meta, &mut self.block)
+            })
+        })
+    }
+}
+
+impl TypeCheckModule for Defer {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Deferred code runs after the enclosing loop has finished
+        meta.with_context_fn(Context::set_is_loop_ctx, false, |meta| {
+            self.block.typecheck(meta)
+        })
+    }
+}
+
+impl TranslateModule for Defer {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let name = format!("__defer_{}", meta.gen_value_id());
+        let defers = if self.is_main {
+            MAIN_DEFERS
+        } else {
+            FUNCTION_DEFERS
+        };
+        // Functions defined with `name()` share the variables of the caller in all shells
+        let mut result = vec![
+            raw_fragment!("{name}() {{"),
+            self.block.translate(meta),
+            fragments!("}"),
+        ];
+        // Prepend the code so that it runs in reverse order of registration
+        let value = raw_fragment!("\"{name}; ${{{defers}}}\"");
+        let stmt =
+            VarStmtFragment::new(defers, Type::Text, value).with_optimization_when_unused(false);
+        result.push(stmt.to_frag());
+        if self.is_main {
+            // Signals exit the script so that the exit trap runs the deferred code,
+            // unless the script handles them with its own handler
+            result.push(raw_fragment!("trap 'eval \"${{{MAIN_DEFERS}}}\"' EXIT"));
+            for (signal, code) in [("INT", 130), ("TERM", 143)] {
+                let handled = signal_handled_variable(signal);
+                result.push(raw_fragment!(
+                    "[ -n \"${{{handled}}}\" ] || trap 'exit {code}' {signal}"
+                ));
+            }
+        }
+        BlockFragment::new(result, false).to_frag()
+    }
+}
+
+/// Declares the variables that hold the deferred code of a function
+pub fn translate_defers_prologue(meta: &TranslateMetadata) -> FragmentKind {
+    if !meta
+        .fun_meta
+        .as_ref()
+        .is_some_and(FunctionMetadata::has_defers)
+    {
+        return FragmentKind::Empty;
+    }
+    let defers = VarStmtFragment::new(FUNCTION_DEFERS, Type::Text, fragments!("\"\""))
+        .with_local(true)
+        .with_optimization_when_unused(false);
+    let status = VarStmtFragment::new(DEFER_STATUS, Type::Int, fragments!("0"))
+        .with_local(true)
+        .with_optimization_when_unused(false);
+    BlockFragment::new(vec![defers.to_frag(), status.to_frag()], true).to_frag()
+}
+
+/// Runs the deferred code of a function when its body ends without returning
+pub fn translate_defers_epilogue(meta: &TranslateMetadata) -> FragmentKind {
+    if !meta
+        .fun_meta
+        .as_ref()
+        .is_some_and(FunctionMetadata::has_defers)
+    {
+        return FragmentKind::Empty;
+    }
+    let run = raw_fragment!("eval \"${{{FUNCTION_DEFERS}}}\"");
+    BlockFragment::new(vec![run], true).to_frag()
+}
+
+/// Returns from a function with given status code after running its deferred code
+pub fn translate_return(meta: &TranslateMetadata, status: FragmentKind) -> FragmentKind {
+    if !meta
+        .fun_meta
+        .as_ref()
+        .is_some_and(FunctionMetadata::has_defers)
+    {
+        return fragments!("return ", status);
+    }
+    let run = raw_fragment!("eval \"${{{FUNCTION_DEFERS}}}\"");
+    if let FragmentKind::Raw(code) = &status {
+        if code.value.parse::<i32>().is_ok() {
+            return BlockFragment::new(vec![run, fragments!("return ", status)], false).to_frag();
+        }
+    }
+    // Deferred code may change the variables that the status code is read from
+    let save =
+        VarStmtFragment::new(DEFER_STATUS, Type::Int, status).with_optimization_when_unused(false);
+    let ret = raw_fragment!("return \"${{{DEFER_STATUS}}}\"");
+    BlockFragment::new(vec![save.to_frag(), run, ret], false).to_frag()
+}
+
+crate::impl_documentation_noop!(Defer);
@@ -1,4 +1,5 @@
 use crate::modules::expression::expr::Expr;
+use crate::modules::function::defer::translate_return;
 use crate::modules::prelude::FragmentKind;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
@@ -8,12 +9,18 @@ use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
+/// Message of the last failure that happened in a function
+pub const FAIL_MESSAGE: &str = "__fail_message";
+
 #[derive(Debug, Clone, AutoKeyword)]
 #[keyword = "fail"]
 pub struct Fail {
     pub expr: Expr,
     pub code: String,
+    pub message: Option<Box<Expr>>,
     pub is_main: bool,
+    /// Ends the background job that runs the body of a parallel loop or a block with a timeout
+    pub is_job: bool,
 }
 
 impl Typed for Fail {
@@ -29,20 +36,29 @@ impl SyntaxModule<ParserMetadata> for Fail {
         Fail {
             expr: Expr::new(),
             code: String::new(),
+            message: None,
             is_main: false,
+            is_job: false,
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         token(meta, "fail")?;
         let tok = meta.get_current_token();
-        if !meta.context.is_fun_ctx && !meta.context.is_main_ctx {
+        if !meta.context.is_fun_ctx && !meta.context.is_main_ctx && !meta.context.is_job_ctx {
             return error!(meta, tok => {
                 message: "Fail statement outside of function or main",
                 comment: "Fail statements can only be used inside of functions or the main block"
             });
         }
+        if meta.context.is_defer_ctx {
+            return error!(
+                meta,
+                tok, "Fail statement cannot be used inside of a defer block"
+            );
+        }
         self.is_main = meta.context.is_main_ctx;
+        self.is_job = meta.context.is_job_ctx;
         match integer(meta, vec![]) {
             Ok(value) => {
                 if value == "0" {
@@ -59,6 +75,11 @@ impl SyntaxModule<ParserMetadata> for Fail {
                 }
             }
         }
+        if token(meta, ",").is_ok() {
+            let mut message = Expr::new();
+            syntax(meta, &mut message)?;
+            self.message = Some(Box::new(message));
+        }
         Ok(())
     }
 }
@@ -87,6 +108,16 @@ impl TypeCheckModule for Fail {
                 }
             }
         }
+        if let Some(message) = &mut self.message {
+            message.typecheck(meta)?;
+            if message.get_type() != Type::Text {
+                let position = message.get_position();
+                return error_pos!(meta, position => {
+                    message: "Invalid failure message",
+                    comment: format!("Failure message must be of type Text, given type: {}", message.get_type())
+                });
+            }
+        }
         Ok(())
     }
 }
@@ -98,7 +129,17 @@ impl TranslateModule for Fail {
         } else {
             raw_fragment!("{}", &self.code)
         };
+        if self.is_job {
+            // The status code is handled once all of the jobs of the loop have finished
+            return fragments!("exit ", translate);
+        }
+        let message = self.message.as_ref().map(|message| message.translate(meta));
         if self.is_main {
+            // The failure is not handled by anyone, so the message is printed right away
+            if let Some(message) = message {
+                let print = fragments!("printf '%s\\n' ", message, " >&2");
+                meta.stmt_queue.push_back(print);
+            }
             fragments!("exit ", translate)
         } else {
             // Clean the return value if the function fails
@@ -113,7 +154,14 @@ impl TranslateModule for Fail {
             )
             .with_optimization_when_unused(false);
             meta.stmt_queue.push_back(stmt.to_frag());
-            fragments!("return ", translate)
+            let message = VarStmtFragment::new(
+                FAIL_MESSAGE,
+                Type::Text,
+                message.unwrap_or_else(|| fragments!("''")),
+            )
+            .with_optimization_when_unused(false);
+            meta.stmt_queue.push_back(message.to_frag());
+            translate_return(meta, translate)
         }
     }
 }
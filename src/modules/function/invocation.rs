@@ -2,10 +2,15 @@ use super::invocation_utils::*;
 use crate::modules::command::modifier::CommandModifier;
 use crate::modules::condition::failure_handler::FailureHandler;
 use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::expression::literal::array::Array;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
-use crate::modules::variable::variable_name_extensions;
-use crate::utils::ShellType;
+use crate::modules::variable::{
+    handle_variable_reference, prevent_job_mutation, variable_name_extensions,
+};
+use crate::utils::context::VariableDecl;
+use crate::utils::function_metadata::FUNCTION_VALUE_RETURN;
+use crate::utils::{pluralize, ShellType};
 use crate::{fragments, raw_fragment};
 use heraclitus_compiler::prelude::*;
 use itertools::izip;
@@ -15,15 +20,21 @@ pub struct FunctionInvocation {
     name: String,
     name_tok: Option<Token>,
     args: Vec<Expr>,
+    /// Whether the argument is an array spread into the rest argument
+    spreads: Vec<bool>,
+    /// Names of the arguments given by name with their tokens
+    names: Vec<Option<(String, Option<Token>)>>,
     refs: Vec<bool>,
     kind: Type,
     variant_id: usize,
     id: usize,
     line: usize,
     col: usize,
-    failure_handler: FailureHandler,
+    failure_handler: Box<FailureHandler>,
     modifier: CommandModifier,
     is_failable: bool,
+    /// Variable holding the function when it is called through a function value
+    value: Option<Box<VariableDecl>>,
 }
 
 impl Typed for FunctionInvocation {
@@ -32,9 +43,46 @@ impl Typed for FunctionInvocation {
     }
 }
 
+impl FunctionInvocation {
+    /// Creates a call of the function with given name and a single argument
+    pub fn with_argument(name: &str, name_tok: Option<Token>, arg: Expr) -> Self {
+        let mut invocation = FunctionInvocation::new();
+        if let Some(tok) = &name_tok {
+            (invocation.line, invocation.col) = tok.pos;
+        }
+        invocation.name = name.to_string();
+        invocation.name_tok = name_tok;
+        invocation
+            .failure_handler
+            .set_function_name(name.to_string());
+        invocation.args = vec![arg];
+        invocation.spreads = vec![false];
+        invocation.names = vec![None];
+        invocation
+    }
+
+    /// Whether the failure of this call is left to the operator that follows it
+    pub fn can_fail(&self) -> bool {
+        self.is_failable && self.failure_handler.is_implicit
+    }
+}
+
+/// Parses the `name:` prefix of an argument given by name
+fn parse_argument_name(meta: &mut ParserMetadata) -> Option<(String, Option<Token>)> {
+    let index = meta.get_index();
+    let tok = meta.get_current_token();
+    match variable(meta, variable_name_extensions()) {
+        Ok(name) if token(meta, ":").is_ok() => Some((name, tok)),
+        _ => {
+            meta.set_index(index);
+            None
+        }
+    }
+}
+
 fn is_ref(expr: &Expr) -> bool {
     match &expr.value {
-        Some(ExprType::VariableGet(var)) => !var.is_variable_modified(),
+        Some(ExprType::VariableGet(var)) => !var.is_variable_modified() && !var.is_function(),
         _ => false,
     }
 }
@@ -47,15 +95,18 @@ impl SyntaxModule<ParserMetadata> for FunctionInvocation {
             name: String::new(),
             name_tok: None,
             args: vec![],
+            spreads: vec![],
+            names: vec![],
             refs: vec![],
             kind: Type::Null,
             variant_id: 0,
             id: 0,
             line: 0,
             col: 0,
-            failure_handler: FailureHandler::new(),
+            failure_handler: Box::new(FailureHandler::new()),
             modifier: CommandModifier::new_expr(),
             is_failable: false,
+            value: None,
         }
     }
 
@@ -85,9 +136,24 @@ impl SyntaxModule<ParserMetadata> for FunctionInvocation {
                 if token(meta, ")").is_ok() {
                     break;
                 }
+                let is_spread = token(meta, "...").is_ok();
+                let name = if is_spread {
+                    None
+                } else {
+                    parse_argument_name(meta)
+                };
+                if name.is_none() && self.names.iter().any(Option::is_some) {
+                    return error!(
+                        meta,
+                        meta.get_current_token(),
+                        "Positional arguments cannot follow named arguments"
+                    );
+                }
                 let mut arg = Expr::new();
                 syntax(meta, &mut arg)?;
                 self.args.push(arg);
+                self.spreads.push(is_spread);
+                self.names.push(name);
                 match token(meta, ")") {
                     Ok(_) => break,
                     Err(_) => token(meta, ",")?,
@@ -103,7 +169,7 @@ impl SyntaxModule<ParserMetadata> for FunctionInvocation {
                 ));
 
             // Try to parse the failed block if present (optional in parse phase)
-            if let Err(Failure::Loud(msg)) = syntax(meta, &mut self.failure_handler) {
+            if let Err(Failure::Loud(msg)) = syntax(meta, &mut *self.failure_handler) {
                 return Err(Failure::Loud(msg));
             }
 
@@ -120,11 +186,103 @@ impl TypeCheckModule for FunctionInvocation {
                 arg.typecheck(meta)?;
             }
 
+            let first_spread = self.spreads.iter().position(|is_spread| *is_spread);
+
+            // Call the function stored in a variable
+            let is_value = matches!(
+                meta.get_var(&self.name).map(|var| &var.kind),
+                Some(Type::Generic | Type::Function(_))
+            );
+            if let (Some(index), true) = (first_spread, is_value) {
+                let pos = self.args[index].get_position();
+                return error_pos!(meta, pos => {
+                    message: format!("Cannot spread arguments into function value '{}'", self.name),
+                    comment: "Arrays can only be spread into the rest argument of a declared function"
+                });
+            }
+            if let (Some((_, tok)), true) = (self.names.iter().flatten().next(), is_value) {
+                return error!(meta, tok.clone(), format!("Cannot pass named arguments to function value '{}'", self.name));
+            }
+            // Arguments of generic functions are not known in the first pass
+            if let Some(Type::Generic) = meta.get_var(&self.name).map(|var| &var.kind) {
+                handle_variable_reference(meta, &self.name_tok, &self.name)?;
+                self.kind = Type::Generic;
+                return Ok(());
+            }
+            if let Some(Type::Function(_)) = meta.get_var(&self.name).map(|var| &var.kind) {
+                let var = handle_variable_reference(meta, &self.name_tok, &self.name)?;
+                let Type::Function(kind) = &var.kind else {
+                    unreachable!("Function value has to be of function type")
+                };
+                let types = self.args.iter().map(Expr::get_type).collect::<Vec<Type>>();
+                handle_function_value_parameters(meta, &self.name, kind, &types, self.name_tok.clone())?;
+                self.kind = *kind.returns.clone();
+                self.refs = vec![false; self.args.len()];
+                self.value = Some(Box::new(var));
+                // Function values cannot fail
+                if self.failure_handler.is_parsed
+                    && !self.failure_handler.is_implicit
+                    && !self.failure_handler.is_try
+                    && !meta.context.is_trust_ctx
+                {
+                    let message = Message::new_warn_at_token(meta, self.name_tok.clone())
+                        .message(format!("Function '{}' cannot fail", self.name))
+                        .comment("You can remove the failure handler block or '?' at the end");
+                    meta.add_message(message);
+                }
+                return Ok(());
+            }
+
             // Look up the function declaration (this requires typecheck phase context)
             self.id = handle_function_reference(meta, self.name_tok.clone(), &self.name)?;
 
             let function_unit = meta.get_fun_declaration(&self.name).unwrap().clone();
-            let expected_arg_count = function_unit.args.len();
+            // The rest argument collects all arguments from its position
+            let rest_index = function_unit.args.iter().position(|arg| arg.is_variadic);
+            if let Some(index) = first_spread {
+                if !matches!(rest_index, Some(rest_index) if index >= rest_index) {
+                    let pos = self.args[index].get_position();
+                    return error_pos!(meta, pos => {
+                        message: format!("Cannot spread arguments into function '{}'", self.name),
+                        comment: "Arrays can only be spread into the rest argument"
+                    });
+                }
+            }
+            let expected_arg_count = rest_index.unwrap_or(function_unit.args.len());
+
+            // Place the named arguments at the positions of the declared arguments
+            if let Some(first_named) = self.names.iter().position(Option::is_some) {
+                let named_args = self.args.split_off(first_named);
+                let names = self.names.split_off(first_named);
+                self.spreads.truncate(first_named);
+                let mut slots: Vec<Option<Expr>> = vec![None; expected_arg_count.saturating_sub(first_named)];
+                for (arg, (name, tok)) in named_args.into_iter().zip(names.into_iter().flatten()) {
+                    let Some(index) = function_unit.args.iter().position(|arg| arg.name == name) else {
+                        return error!(meta, tok, format!("Function '{}' has no argument named '{name}'", self.name));
+                    };
+                    if Some(index) == rest_index {
+                        return error!(meta, tok => {
+                            message: format!("Rest argument '{name}' cannot be given by name"),
+                            comment: "Pass the remaining arguments after the positional arguments instead"
+                        });
+                    }
+                    match index.checked_sub(first_named).and_then(|slot| slots.get_mut(slot)) {
+                        Some(slot @ None) => *slot = Some(arg),
+                        _ => return error!(meta, tok, format!("Argument '{name}' of function '{}' is given more than once", self.name)),
+                    }
+                }
+                // Fill the gaps with the default values
+                for (slot, fun_arg) in slots.into_iter().zip(function_unit.args.iter().skip(first_named)) {
+                    match slot.or_else(|| fun_arg.optional.clone()) {
+                        Some(arg) => {
+                            self.args.push(arg);
+                            self.spreads.push(false);
+                        }
+                        None => return error!(meta, self.name_tok.clone(), format!("Argument '{}' of function '{}' is not given", fun_arg.name, self.name)),
+                    }
+                }
+            }
+
             let actual_arg_count = self.args.len();
             let optional_count = function_unit.args.iter().filter(|arg| arg.optional.is_some()).count();
 
@@ -137,13 +295,53 @@ impl TypeCheckModule for FunctionInvocation {
                     let optionals: Vec<_> = function_unit.args.iter().filter_map(|arg| arg.optional.as_ref()).collect();
                     for exp in optionals.iter().skip(provided_optional){
                         self.args.push((*exp).clone());
+                        self.spreads.push(false);
                     }
                 }
             }
 
+            // Collect the remaining arguments into an array
+            if let Some(index) = rest_index {
+                if self.args.len() < index {
+                    let min_args = index - optional_count;
+                    let txt_arguments = pluralize(min_args, "argument", "arguments");
+                    let txt_given = pluralize(actual_arg_count, "was given", "were given");
+                    return error!(meta, self.name_tok.clone(), format!(
+                        "Function '{}' expects at least {min_args} {txt_arguments}, but {actual_arg_count} {txt_given}",
+                        self.name
+                    ));
+                }
+                let exprs = self.args.split_off(index);
+                let spreads = self.spreads.split_off(index);
+                let position = match exprs.first() {
+                    Some(expr) => expr.get_position(),
+                    None => PositionInfo::from_token(meta, self.name_tok.clone()),
+                };
+                // Without values the array takes the declared type
+                let kind = match &function_unit.args[index].kind {
+                    kind if exprs.is_empty() && kind.is_array() => kind.clone(),
+                    _ if exprs.is_empty() => Type::array_of(Type::Generic),
+                    _ => Type::Generic,
+                };
+                let mut rest = Expr {
+                    value: Some(ExprType::Array(Array::from_rest(exprs, spreads, kind, self.name_tok.clone()))),
+                    kind: Type::Generic,
+                    position: Some(position),
+                };
+                rest.typecheck(meta)?;
+                self.args.push(rest);
+            }
+
             // Check for type inference on reference arguments
             for (arg_expr, fun_arg) in izip!(&mut self.args, &function_unit.args) {
                 if fun_arg.is_ref {
+                    if arg_expr.get_type().is_map() {
+                        let pos = arg_expr.get_position();
+                        return error_pos!(meta, pos => {
+                            message: "Maps cannot be passed by reference",
+                            comment: "Return the modified map from the function instead"
+                        });
+                    }
                     if let (Type::Array(inner), Type::Array(expected_inner)) = (arg_expr.get_type(), &fun_arg.kind) {
                         if *inner == Type::Generic && **expected_inner != Type::Generic {
                             if let Some(ExprType::VariableGet(var)) = &arg_expr.value {
@@ -165,6 +363,7 @@ impl TypeCheckModule for FunctionInvocation {
             for (arg, is_ref) in izip!(self.args.iter(), self.refs.iter()) {
                 if *is_ref {
                     if let Some(ExprType::VariableGet(var)) = &arg.value {
+                        prevent_job_mutation(meta, &self.name_tok, &var.name)?;
                         meta.mark_var_modified(&var.name);
                     }
                 }
@@ -187,7 +386,11 @@ impl TypeCheckModule for FunctionInvocation {
                     });
                 }
                 self.failure_handler.typecheck(meta)?;
-            } else if self.failure_handler.is_parsed && !meta.context.is_trust_ctx {
+            } else if self.failure_handler.is_parsed
+                && !self.failure_handler.is_implicit
+                && !self.failure_handler.is_try
+                && !meta.context.is_trust_ctx
+            {
                 let message = Message::new_warn_at_token(meta, self.name_tok.clone())
                     .message(format!("Function '{}' cannot fail", &self.name))
                     .comment("You can remove the failure handler block or '?' at the end");
@@ -203,7 +406,13 @@ impl TranslateModule for FunctionInvocation {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         // Get the variable prefix based on function name casing
         let prefix = meta.gen_variable_prefix(&self.name);
-        let name = raw_fragment!("{}{}__{}_v{}", prefix, self.name, self.id, self.variant_id);
+        let name = match &self.value {
+            Some(var) => VarExprFragment::new(&var.name, var.kind.clone())
+                .with_global_id(var.global_id)
+                .with_ref(var.is_ref)
+                .to_frag(),
+            None => raw_fragment!("{}{}__{}_v{}", prefix, self.name, self.id, self.variant_id),
+        };
         meta.with_silenced(self.modifier.is_silent || meta.silenced, |meta| {
             let silent = meta.gen_silent().to_frag();
             let suppress = meta.gen_suppress().to_frag();
@@ -292,21 +501,31 @@ impl TranslateModule for FunctionInvocation {
         if self.kind != Type::Null {
             // Get the variable prefix for return values
             let prefix = meta.gen_variable_prefix(&self.name);
-            let invocation_return = format!(
-                "{}ret_{}{}_v{}",
-                prefix, self.name, self.id, self.variant_id
-            );
-            let invocation_instance = format!(
-                "{}ret_{}{}_v{}__{}_{}",
-                prefix, self.name, self.id, self.variant_id, self.line, self.col
-            );
+            let (invocation_return, invocation_instance) = match &self.value {
+                Some(_) => (
+                    FUNCTION_VALUE_RETURN.to_string(),
+                    format!("{FUNCTION_VALUE_RETURN}__{}_{}", self.line, self.col),
+                ),
+                None => (
+                    format!(
+                        "{}ret_{}{}_v{}",
+                        prefix, self.name, self.id, self.variant_id
+                    ),
+                    format!(
+                        "{}ret_{}{}_v{}__{}_{}",
+                        prefix, self.name, self.id, self.variant_id, self.line, self.col
+                    ),
+                ),
+            };
+            // Maps are returned packed into a single string
+            let kind = match &self.kind {
+                Type::Map(_) => Type::Text,
+                kind => kind.clone(),
+            };
             let parsed_invocation_return =
-                VarExprFragment::new(&invocation_return, self.kind.clone()).to_frag();
-            let var_stmt = VarStmtFragment::new(
-                &invocation_instance,
-                self.kind.clone(),
-                parsed_invocation_return,
-            );
+                VarExprFragment::new(&invocation_return, kind.clone()).to_frag();
+            let var_stmt =
+                VarStmtFragment::new(&invocation_instance, kind, parsed_invocation_return);
             meta.push_ephemeral_variable(var_stmt).to_frag()
         } else {
             fragments!("''")
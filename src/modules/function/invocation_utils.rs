@@ -1,10 +1,11 @@
 use crate::modules::typecheck::TypeCheckModule;
-use crate::modules::types::Type;
+use crate::modules::types::{FunctionType, Type};
 use crate::utils::context::{FunctionDecl, VariableDecl, VariableDeclWarn};
 use crate::utils::{pluralize, ParserMetadata};
 use heraclitus_compiler::prelude::*;
 use itertools::izip;
 use similar_string::find_best_similarity;
+use std::collections::HashMap;
 
 // Convert a number to an ordinal number
 // Eg. 1 -> 1st, 2 -> 2nd, 3 -> 3rd, 4 -> 4th
@@ -57,18 +58,43 @@ pub fn run_function_with_args(
         );
     }
     // Check if the function argument types match
+    let mut type_params = HashMap::new();
+    let declared_returns = fun.returns.clone();
     if fun.is_args_typed {
+        let mut bindings = HashMap::new();
         for (index, (arg, given_type)) in izip!(fun.args.iter(), args.iter()).enumerate() {
             let arg_name = &arg.name;
             let arg_type = &arg.kind;
+            if arg_type.has_params() {
+                if let Err(param) = bind_type_params(arg_type, given_type, &mut bindings) {
+                    let fun_name = &fun.name;
+                    let ordinal = ordinal_number(index);
+                    let message = format!("{ordinal} argument '{arg_name}' of function '{fun_name}' expects type '{arg_type}', but '{given_type}' was given");
+                    return match param.and_then(|name| bindings.get(&name).map(|kind| (name, kind)))
+                    {
+                        Some((name, kind)) => error!(meta, tok => {
+                            message: message,
+                            comment: format!("Type parameter '{name}' was already inferred as '{kind}'")
+                        }),
+                        None => error!(meta, tok, message),
+                    };
+                }
+                continue;
+            }
             if !given_type.is_allowed_in(arg_type) {
                 let fun_name = &fun.name;
                 let ordinal = ordinal_number(index);
                 return error!(meta, tok, format!("{ordinal} argument '{arg_name}' of function '{fun_name}' expects type '{arg_type}', but '{given_type}' was given"));
             }
         }
+        // The return type is inferred from the bound type parameters
+        if fun.returns.has_params() {
+            fun.returns = fun.returns.substitute_params(&bindings);
+        }
+        type_params = bindings;
     }
     let mut context = meta.fun_cache.get_context(fun.id).unwrap().clone();
+    context.type_params = type_params;
     let mut block = meta
         .fun_cache
         .get_block(fun.id)
@@ -124,7 +150,7 @@ pub fn run_function_with_args(
                 }
                 // Set the expected return type if specified
                 if fun.returns != Type::Generic {
-                    meta.context.fun_ret_type = Some(fun.returns.clone());
+                    meta.context.fun_ret_type = Some(declared_returns.clone());
                 }
                 // Typecheck the function body
                 if let Err(failure) = block.typecheck(meta) {
@@ -172,6 +198,60 @@ pub fn run_function_with_args(
     }
 }
 
+/// Binds type parameters in the declared argument type to the parts of the given type.
+/// Fails with the name of the type parameter if it was already bound to an incompatible type.
+fn bind_type_params(
+    declared: &Type,
+    given: &Type,
+    bindings: &mut HashMap<String, Type>,
+) -> Result<(), Option<String>> {
+    match (declared, given) {
+        (Type::Param(name), given) => match bindings.get(name) {
+            Some(bound) if *bound != Type::Generic && given.is_allowed_in(bound) => Ok(()),
+            Some(bound) if *bound != Type::Generic && !bound.is_allowed_in(given) => {
+                Err(Some(name.clone()))
+            }
+            // Bind the parameter or widen it to the given type
+            _ => {
+                bindings.insert(name.clone(), given.clone());
+                Ok(())
+            }
+        },
+        (Type::Array(declared), Type::Array(given)) | (Type::Map(declared), Type::Map(given)) => {
+            bind_type_params(declared, given, bindings)
+        }
+        (Type::Tuple(declared), Type::Tuple(given)) if declared.len() == given.len() => declared
+            .iter()
+            .zip(given)
+            .try_for_each(|(declared, given)| bind_type_params(declared, given, bindings)),
+        (Type::Function(declared), Type::Function(given))
+            if declared.args.len() == given.args.len() =>
+        {
+            declared
+                .args
+                .iter()
+                .zip(&given.args)
+                .try_for_each(|(declared, given)| bind_type_params(declared, given, bindings))?;
+            bind_type_params(&declared.returns, &given.returns, bindings)
+        }
+        (Type::Union(types), given) => {
+            // Prefer the members that do not depend on type parameters
+            if types
+                .iter()
+                .any(|kind| !kind.has_params() && given.is_allowed_in(kind))
+            {
+                return Ok(());
+            }
+            match types.iter().find(|kind| kind.has_params()) {
+                Some(kind) => bind_type_params(kind, given, bindings),
+                None => Err(None),
+            }
+        }
+        (_, Type::Generic) => Ok(()),
+        _ => Err(None),
+    }
+}
+
 pub fn handle_function_reference(
     meta: &ParserMetadata,
     tok: Option<Token>,
@@ -212,7 +292,12 @@ pub fn handle_function_parameters(
 
     // On first invocation, run first-pass with declared types (or Generic) to emit correct warnings.
     if !meta.fun_cache.is_first_pass_done(id) {
-        let declared_types: Vec<Type> = fun.args.iter().map(|arg| arg.kind.clone()).collect();
+        // Type parameters are not known yet, so they are treated as generic
+        let declared_types: Vec<Type> = fun
+            .args
+            .iter()
+            .map(|arg| arg.kind.substitute_params(&HashMap::new()))
+            .collect();
         // We set persist to false, because we don't want to cache the function instance
         let _ = meta.with_first_pass_ctx(true, |meta| {
             run_function_with_args(meta, fun.clone(), &declared_types, tok.clone(), false)
@@ -241,6 +326,36 @@ pub fn handle_function_parameters(
     result
 }
 
+pub fn handle_function_value_parameters(
+    meta: &ParserMetadata,
+    name: &str,
+    kind: &FunctionType,
+    args: &[Type],
+    tok: Option<Token>,
+) -> SyntaxResult {
+    // Function values have no optional arguments
+    if kind.args.len() != args.len() {
+        let txt_arguments = pluralize(kind.args.len(), "argument", "arguments");
+        let txt_given = pluralize(args.len(), "was given", "were given");
+        return error!(
+            meta,
+            tok,
+            format!(
+                "Function '{name}' expects {} {txt_arguments}, but {} {txt_given}",
+                kind.args.len(),
+                args.len()
+            )
+        );
+    }
+    for (index, (arg_type, given_type)) in izip!(kind.args.iter(), args.iter()).enumerate() {
+        if !given_type.is_allowed_in(arg_type) {
+            let ordinal = ordinal_number(index);
+            return error!(meta, tok, format!("{ordinal} argument of function '{name}' expects type '{arg_type}', but '{given_type}' was given"));
+        }
+    }
+    Ok(())
+}
+
 fn handle_similar_function(meta: &ParserMetadata, name: &str) -> Option<String> {
     let vars = Vec::from_iter(meta.get_fun_names());
     find_best_similarity(name, &vars).and_then(|(match_name, score)| {
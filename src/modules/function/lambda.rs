@@ -0,0 +1,316 @@
+use super::declaration::FunctionDeclarationArgument;
+use super::declaration_utils::{skip_function_body, translate_args_as_variables};
+use super::defer::{translate_defers_epilogue, translate_defers_prologue};
+use crate::fragments;
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::modules::types::{parse_type, resolve_type, FunctionType, Type, Typed};
+use crate::modules::variable::{handle_identifier_name, variable_name_extensions};
+use crate::raw_fragment;
+use crate::translate::packed::{get_packed_element, pack_value, pack_values, unpack_value};
+use crate::utils::context::{LambdaCaptures, VariableDecl, VariableDeclWarn};
+use crate::utils::function_metadata::FunctionMetadata;
+use crate::utils::ShellType;
+use heraclitus_compiler::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// Counter of the created closures that gives each of them its own captured values
+const LAMBDA_INSTANCES: &str = "__lambda_instances";
+/// Packed captured values of each closure indexed by the number of its instance
+const LAMBDA_CAPTURES: &str = "__lambda_captures";
+
+/// Anonymous function that is used as a value.
+/// Local variables of the enclosing scopes are copied when it is created.
+///
+/// A closure that captures variables can be called after its scope ends, so the slot
+/// with its captured values and the function that passes them are never released.
+/// Creating such a closure in a loop allocates both on every iteration.
+#[derive(Debug, Clone)]
+pub struct Lambda {
+    args: Vec<FunctionDeclarationArgument>,
+    args_global_ids: Vec<Option<usize>>,
+    returns: Type,
+    body: Block,
+    captures: Vec<(VariableDecl, VariableDecl)>,
+    id: usize,
+    has_defers: bool,
+    tok: Option<Token>,
+}
+
+impl Typed for Lambda {
+    fn get_type(&self) -> Type {
+        Type::Function(FunctionType {
+            args: self.args.iter().map(|arg| arg.kind.clone()).collect(),
+            returns: Box::new(self.returns.clone()),
+        })
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Lambda {
+    syntax_name!("Lambda");
+
+    fn new() -> Self {
+        Lambda {
+            args: vec![],
+            args_global_ids: vec![],
+            returns: Type::Generic,
+            body: Block::new().with_condition(),
+            captures: vec![],
+            id: 0,
+            has_defers: false,
+            tok: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.tok = meta.get_current_token();
+        token(meta, "fun")?;
+        token(meta, "(")?;
+        loop {
+            // Skip comments and newlines
+            if token_by(meta, |token| {
+                token.starts_with("//") || token.starts_with('\n')
+            })
+            .is_ok()
+            {
+                continue;
+            }
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            let name_tok = meta.get_current_token();
+            if token(meta, "ref").is_ok() {
+                return error!(
+                    meta,
+                    name_tok, "Anonymous functions cannot take arguments by reference"
+                );
+            }
+            let name = variable(meta, variable_name_extensions())?;
+            if token(meta, ":").is_err() {
+                return error!(meta, name_tok => {
+                    message: format!("Argument '{name}' of anonymous function has to be typed"),
+                    comment: "Anonymous functions are compiled once, so the types of their arguments have to be known"
+                });
+            }
+            let kind = parse_type(meta)?;
+            self.args.push(FunctionDeclarationArgument {
+                name,
+                kind,
+                optional: None,
+                is_ref: false,
+                is_variadic: false,
+                tok: name_tok,
+            });
+            match token(meta, ")") {
+                Ok(_) => break,
+                Err(_) => token(meta, ",")?,
+            };
+        }
+        if token(meta, ":").is_ok() {
+            self.returns = parse_type(meta)?;
+        }
+        let start_pos = meta.get_index();
+        token(meta, "{")?;
+        let (_, _, is_failable) = skip_function_body(meta);
+        meta.set_index(start_pos);
+        if is_failable {
+            return error!(meta, self.tok.clone() => {
+                message: "Anonymous functions cannot fail",
+                comment: "Handle the failure inside of the anonymous function instead"
+            });
+        }
+        let was_fun_ctx = meta.context.is_fun_ctx;
+        meta.context.is_fun_ctx = true;
+        // Failures in the body cannot reach the try block that encloses the lambda
+        let was_try_ctx = std::mem::replace(&mut meta.context.is_try_ctx, false);
+        let was_job_ctx = std::mem::replace(&mut meta.context.is_job_ctx, false);
+        let had_defers = std::mem::replace(&mut meta.context.has_defers, false);
+        let result = syntax(meta, &mut self.body);
+        self.has_defers = std::mem::replace(&mut meta.context.has_defers, had_defers);
+        meta.context.is_try_ctx = was_try_ctx;
+        meta.context.is_job_ctx = was_job_ctx;
+        meta.context.is_fun_ctx = was_fun_ctx;
+        result
+    }
+}
+
+impl TypeCheckModule for Lambda {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let mut seen_argument_names = HashSet::new();
+        for arg in &mut self.args {
+            handle_identifier_name(meta, &arg.name, arg.tok.clone())?;
+            arg.kind = resolve_type(meta, &arg.kind, arg.tok.clone())?;
+            if !arg.kind.is_strictly_typed() {
+                return error!(meta, arg.tok.clone() => {
+                    message: format!("Argument '{}' of anonymous function has to be of concrete type", arg.name),
+                    comment: "Anonymous functions are compiled once, so the types of their arguments have to be known"
+                });
+            }
+            if !seen_argument_names.insert(arg.name.clone()) {
+                return error!(
+                    meta,
+                    arg.tok.clone(),
+                    format!("Argument '{}' is already defined", arg.name)
+                );
+            }
+        }
+        if self.returns != Type::Generic {
+            self.returns = resolve_type(meta, &self.returns, self.tok.clone())?;
+        }
+        self.id = meta.gen_fun_id();
+
+        // The body is checked in a scope that collects the captured variables
+        meta.context.lambda_captures.push(LambdaCaptures {
+            scope: meta.context.scopes.len(),
+            vars: vec![],
+        });
+        let declared_returns = (self.returns != Type::Generic).then(|| self.returns.clone());
+        let prev_ret_type = std::mem::replace(&mut meta.context.fun_ret_type, declared_returns);
+        let was_fun_ctx = std::mem::replace(&mut meta.context.is_fun_ctx, true);
+        let was_loop_ctx = std::mem::replace(&mut meta.context.is_loop_ctx, false);
+        let mut args_global_ids = vec![];
+        let result = meta.with_push_scope(true, |meta| {
+            meta.with_push_scope(true, |meta| {
+                for arg in &self.args {
+                    let var = VariableDecl::new(arg.name.clone(), arg.kind.clone())
+                        .with_warn(VariableDeclWarn::from_token(meta, arg.tok.clone()));
+                    args_global_ids.push(meta.add_var(var));
+                }
+                self.body.typecheck(meta)
+            })
+        });
+        let captures = meta.context.lambda_captures.pop().unwrap();
+        let ret_type = std::mem::replace(&mut meta.context.fun_ret_type, prev_ret_type);
+        meta.context.is_fun_ctx = was_fun_ctx;
+        meta.context.is_loop_ctx = was_loop_ctx;
+        result?;
+
+        if let Some((var, _)) = captures.vars.iter().find(|(var, _)| var.kind.is_map()) {
+            return error!(meta, self.tok.clone() => {
+                message: format!("Anonymous function cannot capture map '{}'", var.name),
+                comment: "Pass the map to the anonymous function as an argument instead"
+            });
+        }
+        if self.returns == Type::Generic {
+            self.returns = ret_type.unwrap_or(Type::Null);
+        }
+        self.args_global_ids = args_global_ids;
+        self.captures = captures.vars;
+        if !self.captures.is_empty() {
+            meta.packed_used = true;
+        }
+        Ok(())
+    }
+}
+
+impl Lambda {
+    fn captures_depth(&self) -> usize {
+        let kinds = self
+            .captures
+            .iter()
+            .map(|(var, _)| var.kind.clone())
+            .collect();
+        Type::Tuple(kinds).packed_depth()
+    }
+
+    /// Stores the captured values of a new closure and defines the function that passes them to the lambda
+    fn translate_instance(&self, meta: &mut TranslateMetadata, name: &str) -> FragmentKind {
+        let values = self
+            .captures
+            .iter()
+            .map(|(var, _)| {
+                let value = VarExprFragment::new(&var.name, var.kind.clone())
+                    .with_global_id(var.global_id)
+                    .with_ref(var.is_ref)
+                    .to_frag();
+                pack_value(meta, value, &var.kind)
+            })
+            .collect();
+        let packed = pack_values(values, self.captures_depth());
+        let instance = format!("${{{LAMBDA_INSTANCES}}}");
+        meta.stmt_queue.push_back(raw_fragment!(
+            "{LAMBDA_INSTANCES}=$(( {LAMBDA_INSTANCES} + 1 ))"
+        ));
+        let captures = VarStmtFragment::new(LAMBDA_CAPTURES, Type::Text, packed)
+            .with_index(raw_fragment!("{instance}"))
+            .with_optimization_when_unused(false);
+        meta.stmt_queue.push_back(captures.to_frag());
+        meta.stmt_queue.push_back(raw_fragment!(
+            "eval \"{name}__{instance}() {{ {name} \\\"\\$@\\\" {instance}; }}\""
+        ));
+        let closure = VarStmtFragment::new(
+            "__lambda",
+            Type::Text,
+            raw_fragment!("\"{name}__{instance}\""),
+        )
+        .with_global_id(meta.gen_value_id());
+        meta.push_ephemeral_variable(closure).to_frag()
+    }
+
+    /// Restores the captured values of the closure that is called
+    fn translate_captures(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let mut queue = VecDeque::new();
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        // The number of the closure instance follows the arguments
+        let instance = self.args.len() + 1;
+        let packed = raw_fragment!("\"${{{LAMBDA_CAPTURES}[${{{instance}}}]}}\"");
+        let depth = self.captures_depth();
+        let mut statements = vec![];
+        for (index, (_, copy)) in self.captures.iter().enumerate() {
+            let element = get_packed_element(meta, packed.clone(), index, depth);
+            let value = unpack_value(meta, element, &copy.kind);
+            statements.extend(meta.stmt_queue.drain(..));
+            let stmt = VarStmtFragment::new(&copy.name, copy.kind.clone(), value)
+                .with_global_id(copy.global_id)
+                .with_local(true)
+                .with_optimization_when_unused(false);
+            statements.push(stmt.to_frag());
+        }
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        BlockFragment::new(statements, true).to_frag()
+    }
+}
+
+impl TranslateModule for Lambda {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let name = format!("lambda__{}", self.id);
+        let prev_fun_meta = meta
+            .fun_meta
+            .replace(FunctionMetadata::new_anonymous(&self.returns).with_defers(self.has_defers));
+        let kinds = self
+            .args
+            .iter()
+            .map(|arg| arg.kind.clone())
+            .collect::<Vec<_>>();
+        let mut result = vec![];
+        // required for the local scope in ksh
+        if matches!(meta.target.shell, ShellType::Ksh) {
+            result.push(raw_fragment!("function {name} {{"));
+        } else {
+            result.push(raw_fragment!("{name}() {{"));
+        }
+        if let Some(args) =
+            translate_args_as_variables(meta, &self.args, &kinds, &self.args_global_ids)
+        {
+            result.push(args);
+        }
+        if !self.captures.is_empty() {
+            result.push(self.translate_captures(meta));
+        }
+        result.push(translate_defers_prologue(meta));
+        result.push(self.body.translate(meta));
+        result.push(translate_defers_epilogue(meta));
+        result.push(fragments!("}"));
+        meta.fun_meta = prev_fun_meta;
+
+        meta.stmt_queue
+            .push_back(BlockFragment::new(result, false).to_frag());
+        if self.captures.is_empty() {
+            return raw_fragment!("{name}");
+        }
+        // Every closure gets its own copy of the captured values
+        self.translate_instance(meta, &name)
+    }
+}
+
+crate::impl_documentation_noop!(Lambda);
@@ -0,0 +1,90 @@
+use super::invocation_utils::run_function_with_args;
+use crate::modules::prelude::*;
+use crate::modules::types::{FunctionType, Type};
+use crate::raw_fragment;
+use crate::utils::context::FunctionDecl;
+use heraclitus_compiler::prelude::*;
+
+/// A named function that is used as a value
+#[derive(Debug, Clone)]
+pub struct FunctionReference {
+    name: String,
+    id: usize,
+    variant_id: usize,
+    kind: FunctionType,
+}
+
+impl FunctionReference {
+    pub fn get_type(&self) -> Type {
+        Type::Function(self.kind.clone())
+    }
+
+    /// Compiles the variant of the function that matches its declared signature
+    pub fn new(
+        meta: &mut ParserMetadata,
+        tok: Option<Token>,
+        fun: FunctionDecl,
+    ) -> Result<Self, Failure> {
+        if !fun.args.iter().all(|arg| arg.kind.is_strictly_typed()) {
+            return error!(meta, tok => {
+                message: format!("Function '{}' must be strictly typed to be used as a value", fun.name),
+                comment: "All function parameters have to be of concrete type"
+            });
+        }
+        if fun.args.iter().any(|arg| arg.is_ref) {
+            return error!(
+                meta,
+                tok,
+                format!(
+                    "Function '{}' takes arguments by reference and cannot be used as a value",
+                    fun.name
+                )
+            );
+        }
+        if fun.is_failable {
+            return error!(meta, tok => {
+                message: format!("Function '{}' can fail and cannot be used as a value", fun.name),
+                comment: "Handle the failure in a function that wraps it instead"
+            });
+        }
+        let args: Vec<Type> = fun.args.iter().map(|arg| arg.kind.clone()).collect();
+        let instance = meta
+            .fun_cache
+            .get_instances(fun.id)
+            .unwrap()
+            .iter()
+            .find(|instance| instance.args == args)
+            .map(|instance| (instance.returns.clone(), instance.variant_id));
+        // Compile the function on demand to get the variant ID
+        let (returns, variant_id) = match instance {
+            Some(instance) => instance,
+            None => {
+                let persist = !meta.first_pass_ctx;
+                run_function_with_args(meta, fun.clone(), &args, tok, persist)?
+            }
+        };
+        if !meta.first_pass_ctx {
+            meta.fun_cache.set_referenced(fun.id, variant_id);
+        }
+        Ok(FunctionReference {
+            name: fun.name,
+            id: fun.id,
+            variant_id,
+            kind: FunctionType {
+                args,
+                returns: Box::new(returns),
+            },
+        })
+    }
+
+    pub fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let prefix = meta.gen_variable_prefix(&self.name);
+        let name = format!("{prefix}{}__{}_v{}", self.name, self.id, self.variant_id);
+        // Functions that return a value are referenced through their wrappers
+        if *self.kind.returns == Type::Null {
+            raw_fragment!("{name}")
+        } else {
+            raw_fragment!("{name}__ref")
+        }
+    }
+}
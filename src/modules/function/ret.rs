@@ -1,7 +1,8 @@
-use crate::fragments;
 use crate::modules::expression::expr::Expr;
+use crate::modules::function::defer::translate_return;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::raw_fragment;
 use crate::utils::function_metadata::FunctionMetadata;
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
@@ -34,6 +35,20 @@ impl SyntaxModule<ParserMetadata> for Return {
                 comment: "Return statements can only be used inside of functions"
             });
         }
+        if meta.context.is_defer_ctx {
+            let tok = meta.get_current_token();
+            return error!(
+                meta,
+                tok, "Return statement cannot be used inside of a defer block"
+            );
+        }
+        if meta.context.is_job_ctx {
+            let tok = meta.get_current_token();
+            return error!(meta, tok => {
+                message: "Return statement cannot be used inside of a background job",
+                comment: "The body of a parallel loop and a block with a timeout run as background jobs"
+            });
+        }
         syntax(meta, &mut self.expr)?;
         Ok(())
     }
@@ -47,11 +62,18 @@ impl TypeCheckModule for Return {
         let expr_type = &self.expr.get_type();
         match ret_type {
             Some(ret_type) => {
-                if !expr_type.is_allowed_in(ret_type) {
+                // Type parameters are checked against the types they are bound to
+                let expected = ret_type.substitute_params(&meta.context.type_params);
+                if !expr_type.is_allowed_in(&expected) {
                     let tok = meta.get_current_token();
+                    let inferred = if ret_type.has_params() {
+                        format!(" (inferred as {expected})")
+                    } else {
+                        String::new()
+                    };
                     return error!(meta, tok => {
                         message: "Return type does not match function return type",
-                        comment: format!("Given type: {}, expected type: {}", expr_type, ret_type)
+                        comment: format!("Given type: {}, expected type: {}{inferred}", expr_type, ret_type)
                     });
                 }
             }
@@ -71,10 +93,15 @@ impl TranslateModule for Return {
             .map(FunctionMetadata::mangled_name)
             .expect("Function name and return type not set");
         let result = self.expr.translate(meta);
-        let var_stmt = VarStmtFragment::new(&fun_name, self.expr.get_type(), result)
-            .with_optimization_when_unused(false);
+        // Maps and tuples are returned packed into a single string
+        let kind = match self.expr.get_type() {
+            Type::Map(_) | Type::Tuple(_) => Type::Text,
+            kind => kind,
+        };
+        let var_stmt =
+            VarStmtFragment::new(&fun_name, kind, result).with_optimization_when_unused(false);
         meta.stmt_queue.push_back(var_stmt.to_frag());
-        fragments!("return 0")
+        translate_return(meta, raw_fragment!("0"))
     }
 }
 
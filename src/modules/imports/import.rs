@@ -4,7 +4,7 @@ use crate::modules::block::Block;
 use crate::modules::prelude::*;
 use crate::modules::variable::variable_name_extensions;
 use crate::stdlib;
-use crate::utils::context::{Context, FunctionDecl, VariableDecl};
+use crate::utils::context::{Context, FunctionDecl, TypeDecl, VariableDecl};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 use std::fs;
@@ -46,11 +46,26 @@ impl Import {
         meta: &mut ParserMetadata,
         mut pub_funs: Vec<FunctionDecl>,
         mut pub_vars: Vec<VariableDecl>,
+        mut pub_types: Vec<TypeDecl>,
     ) -> SyntaxResult {
         if !self.is_all {
             for def in self.wants.iter() {
                 let ImportWant { name, alias, token } = def;
 
+                if let Some(decl) = pub_types.iter_mut().find(|decl| &decl.name == name) {
+                    if let Some(alias) = alias {
+                        decl.name = alias.clone();
+                    }
+
+                    decl.is_public = self.is_pub;
+                    if !meta.add_type_declaration(decl.clone()) {
+                        return error!(meta, token.clone() => {
+                            message: format!("Type '{}' is already defined", decl.name)
+                        });
+                    }
+                    continue;
+                }
+
                 let found_fn = pub_funs.iter_mut().find(|fun| &fun.name == name);
                 let found_var = pub_vars.iter_mut().find(|var| &var.name == name);
 
@@ -94,6 +109,17 @@ impl Import {
                 }
             }
         } else {
+            for mut decl in pub_types {
+                // Determine if imported types should be exported further
+                decl.is_public = self.is_pub;
+                let name = decl.name.clone();
+                if !meta.add_type_declaration(decl) {
+                    return error!(meta, self.token_import.clone() => {
+                        message: format!("Type '{}' is already defined", name)
+                    });
+                }
+            }
+
             for mut var in pub_vars {
                 // Determine if imported variables should be exported further
                 var.is_public = self.is_pub;
@@ -160,7 +186,7 @@ impl Import {
     fn load_or_compile(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         // If the import was already cached, we don't need to recompile it
         match meta.import_cache.get_imports(Some(self.path.value.clone())) {
-            Some(pubs) => self.add_imported_deps(meta, pubs.0, pubs.1),
+            Some(pubs) => self.add_imported_deps(meta, pubs.0, pubs.1, pubs.2),
             None => self.compile_import(meta),
         }
     }
@@ -187,9 +213,15 @@ impl Import {
                     block,
                     context.pub_funs.clone(),
                     context.pub_vars.clone(),
+                    context.pub_types.clone(),
                 );
                 // Handle exports (add to current file)
-                self.add_imported_deps(meta, context.pub_funs, context.pub_vars)?;
+                self.add_imported_deps(
+                    meta,
+                    context.pub_funs,
+                    context.pub_vars,
+                    context.pub_types,
+                )?;
                 Ok(())
             }
             Err(err) => Err(Failure::Loud(err)),
@@ -1,7 +1,7 @@
-use crate::fragments;
 use crate::modules::prelude::*;
 use crate::translate::module::TranslateModule;
-use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
+use crate::utils::metadata::{ParserMetadata, ShellLoop, TranslateMetadata};
+use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -40,8 +40,12 @@ impl TypeCheckModule for Break {
 }
 
 impl TranslateModule for Break {
-    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
-        fragments!("break")
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Try blocks that are inside of the loop run in shell loops as well
+        match meta.loop_depth(ShellLoop::Loop) {
+            Some(depth) if depth > 1 => raw_fragment!("break {depth}"),
+            _ => fragments!("break"),
+        }
     }
 }
 
@@ -1,5 +1,6 @@
-use crate::fragments;
 use crate::modules::prelude::*;
+use crate::utils::metadata::ShellLoop;
+use crate::{fragments, raw_fragment};
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -38,8 +39,12 @@ impl TypeCheckModule for Continue {
 }
 
 impl TranslateModule for Continue {
-    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
-        fragments!("continue")
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        // Try blocks that are inside of the loop run in shell loops as well
+        match meta.loop_depth(ShellLoop::Loop) {
+            Some(depth) if depth > 1 => raw_fragment!("continue {depth}"),
+            _ => fragments!("continue"),
+        }
     }
 }
 
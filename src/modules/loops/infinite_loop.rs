@@ -2,6 +2,7 @@ use crate::fragments;
 use crate::modules::block::Block;
 use crate::modules::prelude::*;
 use crate::utils::context::Context;
+use crate::utils::metadata::ShellLoop;
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -43,7 +44,7 @@ impl TranslateModule for InfiniteLoop {
             vec![
                 fragments!("while :"),
                 fragments!("do"),
-                self.block.translate(meta),
+                meta.with_loop(ShellLoop::Loop, |meta| self.block.translate(meta)),
                 fragments!("done"),
             ],
             false,
@@ -3,16 +3,20 @@ use heraclitus_compiler::prelude::*;
 use crate::modules::block::Block;
 use crate::modules::builtin::lines::LinesInvocation;
 use crate::modules::expression::expr::{Expr, ExprType};
+use crate::modules::loops::utils::iter_loop_map::IterLoopMap;
 use crate::modules::loops::utils::iter_loop_range::IterLoopRange;
+use crate::modules::loops::utils::parallel::Parallel;
 use crate::modules::prelude::*;
 use crate::modules::prelude::{FragmentKind, RawFragment};
 use crate::modules::types::{Type, Typed};
 use crate::modules::variable::variable_name_extensions;
 use crate::translate::fragments::get_variable_name;
 use crate::translate::module::TranslateModule;
+use crate::translate::packed::unpack_value;
 use crate::utils::context::{Context, VariableDecl, VariableDeclWarn};
-use crate::utils::metadata::{ParserMetadata, TranslateMetadata};
+use crate::utils::metadata::{ParserMetadata, ShellLoop, TranslateMetadata};
 use crate::{fragments, raw_fragment};
+use std::collections::VecDeque;
 
 #[derive(Debug, Clone, amber_meta::AutoKeyword)]
 #[keyword = "for"]
@@ -27,6 +31,8 @@ pub struct IterLoop {
     pub iter_global_id: Option<usize>,
     pub iter_type: Type,
     pub iter_index_tok: Option<Token>,
+    /// Runs the loop bodies as background jobs
+    pub parallel: Option<Box<Parallel>>,
 }
 
 impl SyntaxModule<ParserMetadata> for IterLoop {
@@ -43,11 +49,28 @@ impl SyntaxModule<ParserMetadata> for IterLoop {
             iter_global_id: None,
             iter_type: Type::Generic,
             iter_index_tok: None,
+            parallel: None,
         }
     }
 
     fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
-        token(meta, "for")?;
+        if meta
+            .get_current_token()
+            .is_some_and(|tok| tok.word == "parallel")
+        {
+            let mut parallel = Parallel::new();
+            syntax(meta, &mut parallel)?;
+            self.parallel = Some(Box::new(parallel));
+            let tok = meta.get_current_token();
+            if token(meta, "for").is_err() {
+                return error!(
+                    meta,
+                    tok, "Expected a 'for' loop after the parallel modifier"
+                );
+            }
+        } else {
+            token(meta, "for")?;
+        }
         self.iter_name_tok = meta.get_current_token();
         self.iter_name = variable(meta, variable_name_extensions())?;
         if token(meta, ",").is_ok() {
@@ -60,15 +83,41 @@ impl SyntaxModule<ParserMetadata> for IterLoop {
         // Parse iterable expression
         syntax(meta, &mut self.iter_expr)?;
         // Parse loop body
-        syntax(meta, &mut self.block)?;
-        Ok(())
+        let Some(parallel) = self.parallel.as_mut() else {
+            return syntax(meta, &mut self.block);
+        };
+        // Failures that are not handled in the body end its job
+        meta.with_context_fn(Context::set_is_job_ctx, true, |meta| {
+            meta.with_context_fn(Context::set_is_try_ctx, true, |meta| {
+                meta.with_context_fn(Context::set_is_trust_ctx, false, |meta| {
+                    syntax(meta, &mut self.block)
+                })
+            })
+        })?;
+        parallel.parse_failure_handler(meta)
     }
 }
 
 impl TranslateModule for IterLoop {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        match &self.parallel {
+            Some(parallel) => {
+                let shell_loop = self.translate_loop(meta);
+                parallel.translate_loop(meta, shell_loop)
+            }
+            None => self.translate_loop(meta),
+        }
+    }
+}
+
+impl IterLoop {
+    fn translate_loop(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let iter_lines = self.iterates_lines();
 
+        if self.iter_expr.get_type().is_map() {
+            return self.translate_map_loop(meta);
+        }
+
         // Optimize range loops
         if iter_lines.is_none() {
             if let Some(ExprType::Range(range)) = &self.iter_expr.value {
@@ -77,7 +126,13 @@ impl TranslateModule for IterLoop {
         }
 
         let iter_name_str = get_variable_name(&self.iter_name, self.iter_global_id);
-        let iter_name = raw_fragment!("{}", iter_name_str);
+        // Elements of nested arrays are iterated as packed strings and unpacked in the body
+        let unpack = (iter_lines.is_none() && self.iter_type.is_array())
+            .then(|| self.translate_unpack(meta, &iter_name_str));
+        let iter_name = match unpack {
+            Some(_) => raw_fragment!("{iter_name_str}_packed"),
+            None => raw_fragment!("{}", iter_name_str),
+        };
 
         let fifo_var = format!("__AMBER_FIFO_{}", meta.gen_value_id());
         let pid_var = format!("__AMBER_PID_{}", meta.gen_value_id());
@@ -156,38 +211,47 @@ impl TranslateModule for IterLoop {
             None => fragments!("done"),
         };
 
+        let mut body = vec![];
+        body.extend(unpack);
+        body.push(self.translate_body(meta));
+
         match (self.iter_index.as_ref(), self.iter_index_global_id) {
             (Some(index), global_id) => {
                 let indent = TranslateMetadata::single_indent();
                 let index = get_variable_name(index, global_id);
-                BlockFragment::new(
-                    vec![
-                        RawFragment::from(format!("{index}=0;")).to_frag(),
-                        for_loop_prefix,
-                        self.block.translate(meta),
-                        RawFragment::from(format!("{indent}(( {index}++ )) || true")).to_frag(),
-                        for_loop_suffix,
-                    ],
-                    false,
-                )
-                .to_frag()
+                let mut statements = vec![
+                    RawFragment::from(format!("{index}=0;")).to_frag(),
+                    for_loop_prefix,
+                ];
+                statements.extend(body);
+                statements
+                    .push(RawFragment::from(format!("{indent}(( {index}++ )) || true")).to_frag());
+                statements.push(for_loop_suffix);
+                BlockFragment::new(statements, false).to_frag()
+            }
+            _ => {
+                let mut statements = vec![for_loop_prefix];
+                statements.extend(body);
+                statements.push(for_loop_suffix);
+                BlockFragment::new(statements, false).to_frag()
             }
-            _ => BlockFragment::new(
-                vec![for_loop_prefix, self.block.translate(meta), for_loop_suffix],
-                false,
-            )
-            .to_frag(),
         }
     }
 }
 
 impl TypeCheckModule for IterLoop {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if let Some(parallel) = self.parallel.as_mut() {
+            parallel.typecheck(meta)?;
+        }
         self.iter_expr.typecheck(meta)?;
 
         // Determine iterator type after typechecking
         self.iter_type = match self.iter_expr.get_type() {
             Type::Array(kind) => *kind,
+            // Iterating over a map with a single iterator yields its keys
+            Type::Map(kind) if self.iter_index.is_some() => *kind,
+            Type::Map(_) => Type::Text,
             t if t.is_allowed_in(&Type::array_of(Type::Generic)) => Type::Generic,
             _ => {
                 let pos = self.iter_expr.get_position();
@@ -195,6 +259,11 @@ impl TypeCheckModule for IterLoop {
             }
         };
 
+        // Elements of nested arrays are unpacked in every iteration
+        if self.iter_type.is_array() {
+            meta.packed_used = true;
+        }
+
         // Create iterator variable
         meta.with_push_scope(true, |meta| {
             let var = VariableDecl::new(self.iter_name.clone(), self.iter_type.clone()).with_warn(
@@ -202,25 +271,61 @@ impl TypeCheckModule for IterLoop {
             );
             self.iter_global_id = meta.add_var(var);
             if let Some(index) = self.iter_index.as_ref() {
-                let var = VariableDecl::new(index.clone(), Type::Int).with_warn(
+                let index_type = if self.iter_expr.get_type().is_map() {
+                    Type::Text
+                } else {
+                    Type::Int
+                };
+                let var = VariableDecl::new(index.clone(), index_type).with_warn(
                     VariableDeclWarn::from_token(meta, self.iter_index_tok.clone()),
                 );
                 self.iter_index_global_id = meta.add_var(var);
             }
             // Save loop context state and set it to true
-            meta.with_context_fn(Context::set_is_loop_ctx, true, |meta| {
-                // Type-check the loop body
-                self.block.typecheck(meta)?;
-                Ok(())
+            // unless the body runs as a job that cannot leave the loop
+            let is_loop_ctx = self.parallel.is_none();
+            let job_scope = match self.parallel {
+                Some(_) => Some(meta.context.scopes.len() - 1),
+                None => meta.context.job_scope,
+            };
+            meta.with_context_fn(Context::set_is_loop_ctx, is_loop_ctx, |meta| {
+                meta.with_context_fn(Context::set_job_scope, job_scope, |meta| {
+                    // Type-check the loop body
+                    self.block.typecheck(meta)
+                })
             })?;
             Ok(())
         })?;
 
-        Ok(())
+        match self.parallel.as_mut() {
+            Some(parallel) => parallel.typecheck_failure_handler(meta),
+            None => Ok(()),
+        }
     }
 }
 
 impl IterLoop {
+    /// Translates the loop body that runs as a background job in a parallel loop
+    pub fn translate_body(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        match &self.parallel {
+            Some(parallel) => parallel.translate_job(meta, |meta| self.block.translate(meta)),
+            None => meta.with_loop(ShellLoop::Loop, |meta| self.block.translate(meta)),
+        }
+    }
+
+    fn translate_unpack(&self, meta: &mut TranslateMetadata, iter_name: &str) -> FragmentKind {
+        let mut queue = VecDeque::new();
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        let packed = VarExprFragment::new(&format!("{iter_name}_packed"), Type::Text).to_frag();
+        let value = unpack_value(meta, packed, &self.iter_type);
+        let mut statements = meta.stmt_queue.drain(..).collect::<Vec<_>>();
+        std::mem::swap(&mut meta.stmt_queue, &mut queue);
+        let stmt = VarStmtFragment::new(&self.iter_name, self.iter_type.clone(), value)
+            .with_global_id(self.iter_global_id);
+        statements.push(stmt.to_frag());
+        BlockFragment::new(statements, true).to_frag()
+    }
+
     fn iterates_lines(&self) -> Option<LinesInvocation> {
         if let Some(ExprType::LinesInvocation(value)) = &self.iter_expr.value {
             Some(value.clone())
@@ -0,0 +1,55 @@
+use crate::modules::loops::iter_loop::IterLoop;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::fragments::get_variable_name;
+use crate::translate::fragments::var_expr::{format_position, VarIndexValue};
+use crate::translate::map::{get_map_keys, get_map_variable};
+use crate::{fragments, raw_fragment};
+
+/// Trait to handle map loop translations.
+///
+/// Loops over maps (e.g. `for key, value in map`) iterate over the keys
+/// of the associative array and look up the value for every key.
+pub trait IterLoopMap {
+    fn translate_map_loop(&self, meta: &mut TranslateMetadata) -> FragmentKind;
+}
+
+impl IterLoopMap for IterLoop {
+    fn translate_map_loop(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let kind = self.iter_expr.get_type();
+        let value = self.iter_expr.translate(meta);
+        let map = get_map_variable(meta, value, &kind);
+        let keys = get_map_keys(meta, &map);
+        let mut body = self.translate_body(meta);
+
+        // With a single iterator we only iterate over the keys
+        let (key_name, key_global_id) = match self.iter_index.as_ref() {
+            Some(index) => (index, self.iter_index_global_id),
+            None => (&self.iter_name, self.iter_global_id),
+        };
+        if self.iter_index.is_some() {
+            let key = VarExprFragment::new(key_name, Type::Text).with_global_id(key_global_id);
+            let mut value = map
+                .with_index_by_value(VarIndexValue::Key(key.to_frag()))
+                .with_index_pos(format_position(self.iter_expr.position.as_ref()));
+            value.kind = self.iter_type.clone();
+            let stmt =
+                VarStmtFragment::new(&self.iter_name, self.iter_type.clone(), value.to_frag())
+                    .with_global_id(self.iter_global_id);
+            if let FragmentKind::Block(block) = &mut body {
+                block.statements.insert(0, stmt.to_frag());
+            }
+        }
+
+        let key_name = get_variable_name(key_name, key_global_id);
+        BlockFragment::new(
+            vec![
+                fragments!(raw_fragment!("for {key_name} in "), keys, "; do"),
+                body,
+                fragments!("done"),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+}
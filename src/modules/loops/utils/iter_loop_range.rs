@@ -112,7 +112,7 @@ impl IterLoopRange for IterLoop {
         let op = raw_fragment!("{}", if range.neq { "<" } else { "<=" });
         let (index_init, index_update) = self.translate_range_loop_index_fragments();
 
-        let body = self.block.translate(meta);
+        let body = self.translate_body(meta);
 
         let init = fragments!(iter_name.clone(), "=", from_var, index_init);
         // We do a trick here by multiplying by dir so that we can use the same comparison operator
@@ -160,7 +160,7 @@ impl IterLoopRange for IterLoop {
         );
         let (index_init, index_update) = self.translate_range_loop_index_fragments();
 
-        let body = self.block.translate(meta);
+        let body = self.translate_body(meta);
         let (op, step) = if from_val <= to_val {
             (
                 raw_fragment!("{}", if range.neq { "<" } else { "<=" }),
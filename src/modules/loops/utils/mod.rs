@@ -1 +1,3 @@
+pub mod iter_loop_map;
 pub mod iter_loop_range;
+pub mod parallel;
@@ -0,0 +1,227 @@
+use crate::modules::condition::failure_handler::FailureHandler;
+use crate::modules::expression::expr::Expr;
+use crate::modules::prelude::*;
+use crate::modules::types::{Type, Typed};
+use crate::translate::fragments::get_variable_name;
+use crate::utils::metadata::ShellLoop;
+use crate::utils::ShellType;
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Limit of the loop bodies that run at once as background jobs
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "parallel"]
+#[kind = "stmt"]
+pub struct Parallel {
+    limit: Expr,
+    failure_handler: FailureHandler,
+    id: Option<usize>,
+}
+
+impl Parallel {
+    /// Parses the handler of the failures of all jobs that follows the loop
+    pub fn parse_failure_handler(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if let Err(err) = syntax(meta, &mut self.failure_handler) {
+            return match err {
+                Failure::Quiet(pos) => error_pos!(meta, pos => {
+                    message: "The parallel loop can fail and requires explicit failure handling. Use '?', 'failed', 'succeeded', or 'exited' to manage its result.",
+                    comment: "The status code of the failed loop body is passed to the failure handler once all of the jobs have finished"
+                }),
+                _ => Err(err),
+            };
+        }
+        Ok(())
+    }
+
+    /// Typechecks the handler of the failures outside of the scope of the loop
+    pub fn typecheck_failure_handler(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.failure_handler.typecheck(meta)
+    }
+
+    fn var_name(&self, name: &str) -> String {
+        get_variable_name(name, self.id)
+    }
+
+    /// Waits for the oldest running job and stores its status code when it failed
+    fn translate_wait_oldest(&self) -> FragmentKind {
+        let jobs = self.var_name("__parallel_jobs");
+        let count = self.var_name("__parallel_count");
+        let pid = self.var_name("__parallel_pid");
+        let status = self.var_name("__parallel_status");
+        BlockFragment::new(
+            vec![
+                raw_fragment!("{pid}=\"${{{jobs}%% *}}\""),
+                raw_fragment!("{jobs}=\"${{{jobs}#* }}\""),
+                raw_fragment!("{count}=$(( {count} - 1 ))"),
+                raw_fragment!("wait \"${{{pid}}}\" || {status}=$?"),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+
+    /// Waits for whichever job finishes first and stores its status code when it failed.
+    /// Shells without `wait -n` wait for the oldest job instead.
+    fn translate_wait_any(&self, meta: &TranslateMetadata) -> FragmentKind {
+        if !matches!(meta.target.shell, ShellType::BashModern) {
+            return self.translate_wait_oldest();
+        }
+        let jobs = self.var_name("__parallel_jobs");
+        let count = self.var_name("__parallel_count");
+        let pid = self.var_name("__parallel_pid");
+        let running = self.var_name("__parallel_running");
+        let status = self.var_name("__parallel_status");
+        // The job that finished may belong to the script, so the finished jobs
+        // of the loop are looked up and their status codes are read again
+        BlockFragment::new(
+            vec![
+                raw_fragment!("wait -n"),
+                raw_fragment!("{running}=''"),
+                raw_fragment!("for {pid} in ${{{jobs}}}; do"),
+                BlockFragment::new(
+                    vec![
+                        raw_fragment!("if kill -0 \"${{{pid}}}\" 2>/dev/null; then"),
+                        BlockFragment::new(
+                            vec![raw_fragment!("{running}=\"${{{running}}}${{{pid}}} \"")],
+                            true,
+                        )
+                        .to_frag(),
+                        fragments!("else"),
+                        BlockFragment::new(
+                            vec![
+                                raw_fragment!("{count}=$(( {count} - 1 ))"),
+                                raw_fragment!("wait \"${{{pid}}}\" || {status}=$?"),
+                            ],
+                            true,
+                        )
+                        .to_frag(),
+                        fragments!("fi"),
+                    ],
+                    true,
+                )
+                .to_frag(),
+                fragments!("done"),
+                raw_fragment!("{jobs}=\"${{{running}}}\""),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+
+    /// Runs the translated loop body as a background job once there is room for it
+    pub fn translate_job(
+        &self,
+        meta: &mut TranslateMetadata,
+        body: impl FnOnce(&mut TranslateMetadata) -> FragmentKind,
+    ) -> FragmentKind {
+        let jobs = self.var_name("__parallel_jobs");
+        let count = self.var_name("__parallel_count");
+        let limit = self.var_name("__parallel_limit");
+        let wait = self.translate_wait_any(meta);
+        let body = meta.with_loop(ShellLoop::Job, body);
+        BlockFragment::new(
+            vec![
+                raw_fragment!("while [ \"${{{count}}}\" -ge \"${{{limit}}}\" ]; do"),
+                BlockFragment::new(vec![wait], true).to_frag(),
+                fragments!("done"),
+                fragments!("("),
+                body,
+                fragments!(") &"),
+                raw_fragment!("{jobs}=\"${{{jobs}}}$! \""),
+                raw_fragment!("{count}=$(( {count} + 1 ))"),
+            ],
+            true,
+        )
+        .to_frag()
+    }
+
+    /// Waits for all jobs of the translated loop and handles the failures
+    pub fn translate_loop(
+        &self,
+        meta: &mut TranslateMetadata,
+        shell_loop: FragmentKind,
+    ) -> FragmentKind {
+        let is_local = meta.fun_meta.is_some();
+        let limit = self.limit.translate(meta);
+        let init = |name: &str, kind: Type, value: FragmentKind| {
+            VarStmtFragment::new(name, kind, value)
+                .with_global_id(self.id)
+                .with_local(is_local)
+                .with_optimization_when_unused(false)
+                .to_frag()
+        };
+        let limit_name = self.var_name("__parallel_limit");
+        let jobs = self.var_name("__parallel_jobs");
+        let status = VarExprFragment::new("__parallel_status", Type::Int)
+            .with_global_id(self.id)
+            .to_frag();
+        BlockFragment::new(
+            vec![
+                init("__parallel_limit", Type::Int, limit),
+                // The loop runs at least one job at once
+                raw_fragment!("[ \"${{{limit_name}}}\" -ge 1 ] || {limit_name}=1"),
+                init("__parallel_count", Type::Int, raw_fragment!("0")),
+                init("__parallel_jobs", Type::Text, raw_fragment!("''")),
+                init("__parallel_running", Type::Text, raw_fragment!("''")),
+                init("__parallel_pid", Type::Int, raw_fragment!("0")),
+                init("__parallel_status", Type::Int, raw_fragment!("0")),
+                shell_loop,
+                raw_fragment!("while [ -n \"${{{jobs}}}\" ]; do"),
+                BlockFragment::new(vec![self.translate_wait_oldest()], true).to_frag(),
+                fragments!("done"),
+                self.failure_handler.translate_status(meta, status),
+            ],
+            false,
+        )
+        .to_frag()
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for Parallel {
+    syntax_name!("Parallel");
+
+    fn new() -> Self {
+        Parallel {
+            limit: Expr::new(),
+            failure_handler: FailureHandler::new(),
+            id: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        token(meta, "parallel")?;
+        token(meta, "(")?;
+        syntax(meta, &mut self.limit)?;
+        token(meta, ")")?;
+        Ok(())
+    }
+}
+
+impl TypeCheckModule for Parallel {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        self.limit.typecheck(meta)?;
+        let limit_type = self.limit.get_type();
+        if limit_type != Type::Int {
+            let position = self.limit.get_position();
+            return error_pos!(meta, position => {
+                message: "Limit of the parallel loop must be of type Int",
+                comment: format!("Given type: {limit_type}")
+            });
+        }
+        if self
+            .limit
+            .get_integer_value()
+            .is_some_and(|limit| limit < 1)
+        {
+            let position = self.limit.get_position();
+            return error_pos!(
+                meta,
+                position,
+                "Parallel loop has to run at least one job at once"
+            );
+        }
+        self.id = Some(meta.gen_var_id());
+        Ok(())
+    }
+}
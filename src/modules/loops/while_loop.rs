@@ -4,6 +4,7 @@ use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::utils::context::Context;
+use crate::utils::metadata::ShellLoop;
 use amber_meta::AutoKeyword;
 use heraclitus_compiler::prelude::*;
 
@@ -62,7 +63,7 @@ impl TranslateModule for WhileLoop {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let result = vec![
             fragments!("while [ ", self.condition.translate(meta), " != 0 ]; do"),
-            self.block.translate(meta),
+            meta.with_loop(ShellLoop::Loop, |meta| self.block.translate(meta)),
             fragments!("done"),
         ];
         BlockFragment::new(result, false).to_frag()
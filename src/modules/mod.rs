@@ -5,10 +5,12 @@ use heraclitus_compiler::{
 
 use crate::utils::ParserMetadata;
 
+pub mod alias;
 pub mod block;
 pub mod builtin;
 pub mod command;
 pub mod condition;
+pub mod enumeration;
 pub mod expression;
 pub mod function;
 pub mod imports;
@@ -17,7 +19,9 @@ pub mod loops;
 pub mod main;
 pub mod prelude;
 pub mod shorthand;
+pub mod signal;
 pub mod statement;
+pub mod structure;
 pub mod test;
 pub mod typecheck;
 pub mod types;
@@ -2,7 +2,8 @@ use crate::modules::expression::expr::Expr;
 use crate::modules::prelude::*;
 use crate::modules::types::Type;
 use crate::modules::variable::{
-    handle_variable_reference, prevent_constant_mutation, variable_name_extensions,
+    handle_variable_reference, prevent_constant_mutation, prevent_job_mutation,
+    variable_name_extensions,
 };
 use crate::translate::compute::translate_computation_eval;
 use crate::translate::compute::ArithOp;
@@ -49,6 +50,7 @@ impl TypeCheckModule for ShorthandSub {
 
         let variable = handle_variable_reference(meta, &self.tok, &self.var)?;
         prevent_constant_mutation(meta, &self.tok, &self.var, variable.is_const)?;
+        prevent_job_mutation(meta, &self.tok, &self.var)?;
         meta.mark_var_modified(&self.var);
         self.kind = variable.kind;
         self.global_id = variable.global_id;
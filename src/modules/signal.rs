@@ -0,0 +1,119 @@
+use crate::modules::block::Block;
+use crate::modules::prelude::*;
+use crate::utils::context::Context;
+use crate::utils::metadata::ParserMetadata;
+use crate::{fragments, raw_fragment};
+use amber_meta::AutoKeyword;
+use heraclitus_compiler::prelude::*;
+
+/// Signals that can be handled by all supported shells
+const SIGNALS: [&str; 12] = [
+    "HUP", "INT", "QUIT", "USR1", "USR2", "PIPE", "ALRM", "TERM", "CHLD", "CONT", "TSTP", "WINCH",
+];
+
+/// Returns the name of the variable that marks the signal as handled by the script
+pub fn signal_handled_variable(signal: &str) -> String {
+    format!("__signal_{signal}")
+}
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "on"]
+pub struct OnSignal {
+    pub signals: Vec<String>,
+    pub block: Block,
+}
+
+impl OnSignal {
+    fn parse_signal(meta: &mut ParserMetadata) -> Result<String, Failure> {
+        let tok = meta.get_current_token();
+        let Ok(word) = token_by(meta, |word| {
+            word.len() > 1 && word.starts_with('"') && word.ends_with('"')
+        }) else {
+            return error!(meta, tok, "Signal name must be a text literal");
+        };
+        let name = word.trim_matches('"');
+        let signal = name.strip_prefix("SIG").unwrap_or(name);
+        if ["KILL", "STOP"].contains(&signal) {
+            return error!(meta, tok => {
+                message: format!("Signal '{name}' cannot be handled"),
+                comment: "Signals KILL and STOP always terminate or stop the process"
+            });
+        }
+        if signal == "EXIT" {
+            return error!(meta, tok => {
+                message: format!("Signal '{name}' cannot be handled"),
+                comment: "Use the 'defer' statement in the main block to run code when the script exits"
+            });
+        }
+        if !SIGNALS.contains(&signal) {
+            return error!(meta, tok => {
+                message: format!("Unknown signal '{name}'"),
+                comment: format!("Supported signals are: {}", SIGNALS.join(", "))
+            });
+        }
+        Ok(signal.to_string())
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for OnSignal {
+    syntax_name!("On Signal");
+
+    fn new() -> Self {
+        OnSignal {
+            signals: vec![],
+            block: Block::new().with_needs_noop().with_condition(),
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        let tok = meta.get_current_token();
+        token(meta, "on")?;
+        token(meta, "signal")?;
+        if !meta.context.is_main_ctx || meta.context.is_fun_ctx {
+            return error!(meta, tok => {
+                message: "Signal handlers can only be registered in the main block",
+                comment: "Handlers registered inside of functions are reset when the function returns in zsh and ksh"
+            });
+        }
+        token(meta, "(")?;
+        loop {
+            self.signals.push(Self::parse_signal(meta)?);
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            token(meta, ",")?;
+        }
+        // The handler runs outside of the try block that registered it
+        meta.with_context_fn(Context::set_is_try_ctx, false, |meta| {
+            syntax(meta, &mut self.block)
+        })
+    }
+}
+
+impl TypeCheckModule for OnSignal {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // The handler runs outside of the loop that registered it
+        meta.with_context_fn(Context::set_is_loop_ctx, false, |meta| {
+            self.block.typecheck(meta)
+        })
+    }
+}
+
+impl TranslateModule for OnSignal {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        let name = format!("__on_signal_{}", meta.gen_value_id());
+        let mut result = vec![
+            raw_fragment!("{name}() {{"),
+            self.block.translate(meta),
+            fragments!("}"),
+        ];
+        // Deferred code must not replace the handler with its own trap
+        for signal in &self.signals {
+            result.push(raw_fragment!("{}=1", signal_handled_variable(signal)));
+        }
+        result.push(raw_fragment!("trap '{name}' {}", self.signals.join(" ")));
+        BlockFragment::new(result, false).to_frag()
+    }
+}
+
+crate::impl_documentation_noop!(OnSignal);
@@ -1,15 +1,22 @@
 use super::comment::Comment;
 use super::comment_doc::CommentDoc;
 use crate::docs::module::DocumentationModule;
+use crate::modules::alias::declaration::AliasDeclaration;
 use crate::modules::builtin::{
-    cd::Cd, clear::Clear, cp::Cp, disown::Disown, echo::Echo, exit::Exit, lock::Lock, mv::Mv,
-    rm::Rm, sleep::Sleep, touch::Touch, wait::Await,
+    cd::Cd, clear::Clear, cp::Cp, disown::Disown, echo::Echo, exit::Exit, kill::JobKill,
+    lock::Lock, mv::Mv, rm::Rm, sleep::Sleep, touch::Touch, unset::Unset, wait::Await,
+    wait::AwaitAll,
 };
 use crate::modules::command::cmd::Command;
 use crate::modules::command::modifier::CommandModifier;
-use crate::modules::condition::{ifchain::IfChain, ifcond::IfCondition};
+use crate::modules::condition::{
+    ifchain::IfChain, ifcond::IfCondition, match_stmt::Match, try_stmt::Try,
+};
+use crate::modules::enumeration::declaration::EnumDeclaration;
 use crate::modules::expression::expr::Expr;
-use crate::modules::function::{declaration::FunctionDeclaration, fail::Fail, ret::Return};
+use crate::modules::function::{
+    declaration::FunctionDeclaration, defer::Defer, fail::Fail, ret::Return,
+};
 use crate::modules::imports::import::Import;
 use crate::modules::loops::{
     break_stmt::Break, continue_stmt::Continue, infinite_loop::InfiniteLoop, iter_loop::IterLoop,
@@ -21,6 +28,8 @@ use crate::modules::shorthand::{
     add::ShorthandAdd, div::ShorthandDiv, modulo::ShorthandModulo, mul::ShorthandMul,
     sub::ShorthandSub,
 };
+use crate::modules::signal::OnSignal;
+use crate::modules::structure::declaration::StructDeclaration;
 use crate::modules::test::Test;
 use crate::modules::variable::{
     init::VariableInit, init_destruct::VariableInitDestruct, set::VariableSet,
@@ -41,6 +50,8 @@ pub enum StmtType {
     VariableSet(VariableSet),
     VariableSetDestruct(VariableSetDestruct),
     IfCondition(IfCondition),
+    Match(Match),
+    Try(Try),
     IfChain(IfChain),
     ShorthandAdd(ShorthandAdd),
     ShorthandSub(ShorthandSub),
@@ -53,10 +64,15 @@ pub enum StmtType {
     Break(Break),
     Continue(Continue),
     FunctionDeclaration(FunctionDeclaration),
+    StructDeclaration(StructDeclaration),
+    EnumDeclaration(EnumDeclaration),
+    AliasDeclaration(AliasDeclaration),
     Return(Return),
     Fail(Fail),
+    Defer(Defer),
     Import(Import),
     Main(Main),
+    OnSignal(OnSignal),
     Test(Test),
     Cd(Cd),
     Echo(Echo),
@@ -72,8 +88,11 @@ pub enum StmtType {
     Rm(Rm),
     Clear(Clear),
     Await(Await),
+    AwaitAll(AwaitAll),
     Cp(Cp),
     Disown(Disown),
+    JobKill(JobKill),
+    Unset(Unset),
 }
 
 #[derive(Debug, Clone)]
@@ -85,6 +104,9 @@ impl Statement {
     pub fn get_docs_item_name(&self) -> Option<String> {
         match &self.value {
             Some(StmtType::FunctionDeclaration(inner)) => Some(inner.name.clone()),
+            Some(StmtType::StructDeclaration(inner)) => Some(inner.name.clone()),
+            Some(StmtType::EnumDeclaration(inner)) => Some(inner.name.clone()),
+            Some(StmtType::AliasDeclaration(inner)) => Some(inner.name.clone()),
             _ => None,
         }
     }
@@ -106,10 +128,16 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 Import,
                 // Functions
                 FunctionDeclaration,
+                // Types
+                StructDeclaration,
+                EnumDeclaration,
+                AliasDeclaration,
                 Main,
                 Test,
+                OnSignal,
                 Return,
                 Fail,
+                Defer,
                 // Loops
                 InfiniteLoop,
                 IterLoop,
@@ -119,6 +147,8 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 // Conditions
                 IfChain,
                 IfCondition,
+                Match,
+                Try,
                 // Command
                 Echo,
                 Mv,
@@ -131,9 +161,12 @@ impl SyntaxModule<ParserMetadata> for Statement {
                 Rm,
                 Clear,
                 Await,
+                AwaitAll,
                 Cp,
                 Touch,
                 Disown,
+                JobKill,
+                Unset,
                 // Variables
                 VariableInitDestruct,
                 VariableSetDestruct,
@@ -0,0 +1,204 @@
+use crate::modules::function::declaration_utils::is_declaration_comment_doc;
+use crate::modules::prelude::*;
+use crate::modules::statement::comment_doc::CommentDoc;
+use crate::modules::structure::is_on_new_line;
+use crate::modules::types::{
+    is_type_name, parse_type, resolve_type, StructType, Type, MAX_PACKED_DEPTH,
+};
+use crate::modules::variable::{handle_identifier_name, variable_name_extensions};
+use crate::utils::context::TypeDecl;
+use heraclitus_compiler::prelude::*;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone)]
+pub struct StructField {
+    pub name: String,
+    pub kind: Type,
+    pub tok: Option<Token>,
+}
+
+#[derive(Debug, Clone, AutoKeyword)]
+#[keyword = "struct"]
+#[kind = "stmt"]
+pub struct StructDeclaration {
+    pub name: String,
+    pub fields: Vec<StructField>,
+    pub is_public: bool,
+    pub comment: Option<CommentDoc>,
+    /// Token for struct name (for error positioning)
+    pub name_token: Option<Token>,
+}
+
+impl StructDeclaration {
+    fn render_signature(&self) -> String {
+        let public = if self.is_public { "pub " } else { "" };
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| format!("    {}: {}", field.name, field.kind))
+            .join(",\n");
+        format!("{public}struct {} {{\n{fields}\n}}", self.name)
+    }
+}
+
+impl SyntaxModule<ParserMetadata> for StructDeclaration {
+    syntax_name!("Struct Declaration");
+
+    fn new() -> Self {
+        StructDeclaration {
+            name: String::new(),
+            fields: vec![],
+            is_public: false,
+            comment: None,
+            name_token: None,
+        }
+    }
+
+    fn parse(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        // Parse the struct comment
+        if is_declaration_comment_doc(meta, "struct") {
+            let mut comment = CommentDoc::new();
+            syntax(meta, &mut comment)?;
+            self.comment = Some(comment);
+        }
+        self.is_public = token(meta, "pub").is_ok();
+        token(meta, "struct")?;
+        self.name_token = meta.get_current_token();
+        self.name = variable(meta, variable_name_extensions())?;
+        context!(
+            {
+                token(meta, "{")?;
+                loop {
+                    // Skip comments and newlines
+                    if token_by(meta, |token| {
+                        token.starts_with("//") || token.starts_with('\n')
+                    })
+                    .is_ok()
+                    {
+                        continue;
+                    }
+                    if token(meta, "}").is_ok() {
+                        break;
+                    }
+                    let tok = meta.get_current_token();
+                    let name = variable(meta, variable_name_extensions())?;
+                    token(meta, ":")?;
+                    let kind = parse_type(meta)?;
+                    self.fields.push(StructField { name, kind, tok });
+                    if token(meta, "}").is_ok() {
+                        break;
+                    }
+                    // Fields are separated either by a comma or by a new line
+                    if token(meta, ",").is_err() && !is_on_new_line(meta) {
+                        return error!(
+                            meta,
+                            meta.get_current_token(),
+                            "Expected ',' or '}' after struct field"
+                        );
+                    }
+                }
+                Ok(())
+            },
+            |pos| {
+                error_pos!(
+                    meta,
+                    pos,
+                    format!("Failed to parse struct declaration '{}'", self.name)
+                )
+            }
+        )
+    }
+}
+
+impl TypeCheckModule for StructDeclaration {
+    fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if !meta.is_global_scope() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                "Structs can only be declared in the global scope"
+            );
+        }
+        if !is_type_name(&self.name) {
+            return error!(meta, self.name_token.clone() => {
+                message: format!("Struct name '{}' is not in pascal case", self.name),
+                comment: "Struct names have to start with an uppercase letter, for example 'HostConfig'"
+            });
+        }
+        if meta.get_type_declaration(&self.name).is_some() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                format!("Type '{}' is already defined", self.name)
+            );
+        }
+        if self.fields.is_empty() {
+            return error!(
+                meta,
+                self.name_token.clone(),
+                format!("Struct '{}' must have at least one field", self.name)
+            );
+        }
+
+        let mut seen_field_names = HashSet::new();
+        let mut fields = vec![];
+        for field in self.fields.iter_mut() {
+            handle_identifier_name(meta, &field.name, field.tok.clone())?;
+            if !seen_field_names.insert(field.name.clone()) {
+                return error!(
+                    meta,
+                    field.tok.clone(),
+                    format!("Field '{}' is already defined", field.name)
+                );
+            }
+            field.kind = resolve_type(meta, &field.kind, field.tok.clone())?;
+            if !field.kind.is_strictly_typed() || field.kind == Type::Null {
+                return error!(
+                    meta,
+                    field.tok.clone(),
+                    format!("Field '{}' cannot be of type '{}'", field.name, field.kind)
+                );
+            }
+            fields.push((field.name.clone(), field.kind.clone()));
+        }
+
+        let kind = Type::Struct(StructType {
+            name: self.name.clone(),
+            fields,
+        });
+        if kind.packed_depth() > MAX_PACKED_DEPTH {
+            return error!(meta, self.name_token.clone() => {
+                message: format!("Struct '{}' is nested too deeply", self.name),
+                comment: format!("Values can be nested at most {MAX_PACKED_DEPTH} levels deep")
+            });
+        }
+        meta.add_type_declaration(TypeDecl {
+            name: self.name.clone(),
+            kind,
+            is_public: self.is_public,
+        });
+        Ok(())
+    }
+}
+
+impl TranslateModule for StructDeclaration {
+    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+        FragmentKind::Empty
+    }
+}
+
+impl DocumentationModule for StructDeclaration {
+    fn document(&self, meta: &ParserMetadata) -> String {
+        let mut result = vec![];
+        result.push(format!("## `{}`\n", self.name));
+        result.push("```ab".to_string());
+        result.push(self.render_signature());
+        result.push("```\n".to_string());
+        if let Some(comment) = &self.comment {
+            result.push(comment.document(meta));
+        }
+        result.push("".to_string());
+        result.join("\n")
+    }
+}
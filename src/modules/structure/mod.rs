@@ -0,0 +1,18 @@
+use crate::utils::ParserMetadata;
+use heraclitus_compiler::prelude::*;
+
+pub mod declaration;
+
+/// Checks if the current token starts a new line
+pub fn is_on_new_line(meta: &ParserMetadata) -> bool {
+    let index = meta.get_index();
+    match (
+        index
+            .checked_sub(1)
+            .and_then(|index| meta.get_token_at(index)),
+        meta.get_current_token(),
+    ) {
+        (Some(prev), Some(current)) => current.pos.0 > prev.pos.0,
+        _ => false,
+    }
+}
@@ -1,9 +1,13 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 
-use crate::utils::ParserMetadata;
+use crate::utils::{is_all_caps, ParserMetadata};
 use heraclitus_compiler::prelude::*;
 use itertools::Itertools;
 
+/// Maximum nesting depth of values that are packed into a single shell string
+pub const MAX_PACKED_DEPTH: usize = 4;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Type {
     #[default]
@@ -13,16 +17,109 @@ pub enum Type {
     Num,
     Int,
     Array(Box<Type>),
+    /// Associative array with text keys
+    Map(Box<Type>),
     Union(Vec<Type>),
+    /// Fixed number of values of possibly different types, packed like structs
+    Tuple(Vec<Type>),
+    Struct(StructType),
+    Enum(EnumType),
+    Function(FunctionType),
+    /// Distinct type that is stored like its underlying type
+    Nominal(NominalType),
+    /// Type parameter of a generic function that is bound on each invocation
+    Param(String),
     Generic,
 }
 
+/// A user defined record type. Fields are kept in the declaration order,
+/// which is also the order in which they are packed at runtime.
+/// A struct type without fields has not been resolved yet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructType {
+    pub name: String,
+    pub fields: Vec<(String, Type)>,
+}
+
+impl StructType {
+    pub fn unresolved(name: &str) -> Self {
+        StructType {
+            name: name.to_string(),
+            fields: vec![],
+        }
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    /// Returns the position and the type of the field
+    pub fn get_field(&self, name: &str) -> Option<(usize, &Type)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, (field, _))| field == name)
+            .map(|(index, (_, kind))| (index, kind))
+    }
+}
+
+/// A user defined type with a fixed set of named variants.
+/// Values of an enum are stored as the names of their variants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnumType {
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+impl EnumType {
+    pub fn has_variant(&self, name: &str) -> bool {
+        self.variants.iter().any(|variant| variant == name)
+    }
+}
+
+/// Signature of a function that is used as a value.
+/// Values of a function type are stored as the names of the shell functions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    pub args: Vec<Type>,
+    pub returns: Box<Type>,
+}
+
+/// A user defined type that is incompatible with the type it wraps.
+/// Values can be converted between both types only with a cast.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NominalType {
+    pub name: String,
+    pub inner: Box<Type>,
+}
+
 impl Type {
     #[inline]
     pub fn array_of(kind: Type) -> Self {
         Self::Array(Box::new(kind))
     }
 
+    #[inline]
+    pub fn map_of(kind: Type) -> Self {
+        Self::Map(Box::new(kind))
+    }
+
+    /// Background job that is identified by the process id
+    pub fn job() -> Self {
+        Self::Nominal(NominalType {
+            name: "Job".to_string(),
+            inner: Box::new(Type::Int),
+        })
+    }
+
+    /// Span of time that is stored in milliseconds
+    pub fn duration() -> Self {
+        Self::Nominal(NominalType {
+            name: "Duration".to_string(),
+            inner: Box::new(Type::Int),
+        })
+    }
+
     pub fn is_subset_of(&self, other: &Type) -> bool {
         match (self, other) {
             (Type::Generic, Type::Generic) => false,
@@ -33,6 +130,15 @@ impl Type {
                 (Type::Int, Type::Num) => true,
                 (a, b) => a.is_subset_of(b),
             },
+            (Type::Map(current), Type::Map(other)) => match (&**current, &**other) {
+                (current, Type::Generic) if *current != Type::Generic => true,
+                (Type::Int, Type::Num) => true,
+                (a, b) => a.is_subset_of(b),
+            },
+            (Type::Tuple(current), Type::Tuple(other)) => {
+                current.len() == other.len()
+                    && current.iter().zip(other).all(|(a, b)| a.is_subseteq_of(b))
+            }
             (Type::Union(types), other) => types.iter().all(|t| t.is_allowed_in(other)),
             (other, Type::Union(types)) => types.iter().any(|t| other.is_allowed_in(t)),
             _ => false,
@@ -81,6 +187,10 @@ impl Type {
             return **const_type == Type::Generic && **other_type != Type::Generic;
         }
 
+        if let (Type::Map(const_type), Type::Map(other_type)) = (self, other) {
+            return **const_type == Type::Generic && **other_type != Type::Generic;
+        }
+
         false
     }
 
@@ -88,15 +198,100 @@ impl Type {
         matches!(self, Type::Array(_))
     }
 
+    pub fn is_map(&self) -> bool {
+        matches!(self, Type::Map(_))
+    }
+
+    /// Whether the value of this type can be `null`
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Type::Null => true,
+            Type::Union(types) => types.iter().any(Type::is_nullable),
+            _ => false,
+        }
+    }
+
+    /// Returns how many separator levels are needed to pack this value into a single string.
+    /// Scalars need none, arrays and structs need one more than their contents.
+    pub fn packed_depth(&self) -> usize {
+        match self {
+            Type::Array(inner) | Type::Map(inner) => inner.packed_depth() + 1,
+            Type::Struct(structure) => {
+                structure
+                    .fields
+                    .iter()
+                    .map(|(_, kind)| kind.packed_depth())
+                    .max()
+                    .unwrap_or(0)
+                    + 1
+            }
+            Type::Tuple(types) => types.iter().map(Type::packed_depth).max().unwrap_or(0) + 1,
+            _ => 0,
+        }
+    }
+
     pub fn is_strictly_typed(&self) -> bool {
         match self {
-            Type::Generic => false,
+            Type::Generic | Type::Param(_) => false,
             Type::Union(_) => false,
-            Type::Array(inner) => inner.is_strictly_typed(),
+            Type::Array(inner) | Type::Map(inner) => inner.is_strictly_typed(),
+            Type::Tuple(types) => types.iter().all(Type::is_strictly_typed),
+            Type::Function(function) => {
+                function.args.iter().all(Type::is_strictly_typed)
+                    && function.returns.is_strictly_typed()
+            }
             _ => true,
         }
     }
 
+    pub fn has_params(&self) -> bool {
+        self.has_param_by(&|_| true)
+    }
+
+    pub fn has_param(&self, name: &str) -> bool {
+        self.has_param_by(&|param| param == name)
+    }
+
+    fn has_param_by(&self, predicate: &impl Fn(&str) -> bool) -> bool {
+        match self {
+            Type::Param(name) => predicate(name),
+            Type::Array(inner) | Type::Map(inner) => inner.has_param_by(predicate),
+            Type::Union(types) | Type::Tuple(types) => {
+                types.iter().any(|kind| kind.has_param_by(predicate))
+            }
+            Type::Function(function) => {
+                function
+                    .args
+                    .iter()
+                    .any(|kind| kind.has_param_by(predicate))
+                    || function.returns.has_param_by(predicate)
+            }
+            _ => false,
+        }
+    }
+
+    /// Replaces type parameters with their bound types. Unbound parameters become generic.
+    pub fn substitute_params(&self, bindings: &HashMap<String, Type>) -> Type {
+        let substitute_all = |types: &[Type]| {
+            types
+                .iter()
+                .map(|kind| kind.substitute_params(bindings))
+                .collect()
+        };
+        match self {
+            Type::Param(name) => bindings.get(name).cloned().unwrap_or(Type::Generic),
+            Type::Array(inner) => Type::array_of(inner.substitute_params(bindings)),
+            Type::Map(inner) => Type::map_of(inner.substitute_params(bindings)),
+            Type::Union(types) => Type::Union(substitute_all(types)),
+            Type::Tuple(types) => Type::Tuple(substitute_all(types)),
+            Type::Function(function) => Type::Function(FunctionType {
+                args: substitute_all(&function.args),
+                returns: Box::new(function.returns.substitute_params(bindings)),
+            }),
+            kind => kind.clone(),
+        }
+    }
+
     // Checks if two types can possibly intersect
     pub fn can_intersect(&self, other: &Type) -> bool {
         match (self, other) {
@@ -107,6 +302,13 @@ impl Type {
             (target, Type::Union(types)) => types.iter().any(|t| target.can_intersect(t)),
             // Array types
             (Type::Array(inner_a), Type::Array(inner_b)) => inner_a.can_intersect(inner_b),
+            // Map types
+            (Type::Map(inner_a), Type::Map(inner_b)) => inner_a.can_intersect(inner_b),
+            // Tuple types
+            (Type::Tuple(types_a), Type::Tuple(types_b)) => {
+                types_a.len() == types_b.len()
+                    && types_a.iter().zip(types_b).all(|(a, b)| a.can_intersect(b))
+            }
             // Generic can be anything
             (Type::Generic, _) | (_, Type::Generic) => true,
             // Different primitive types never intersect
@@ -130,7 +332,26 @@ impl Display for Type {
                     write!(f, "[{t}]")
                 }
             }
+            Type::Map(t) => {
+                if **t == Type::Generic {
+                    write!(f, "[Text:]")
+                } else {
+                    write!(f, "[Text: {t}]")
+                }
+            }
             Type::Union(types) => write!(f, "{}", types.iter().map(|t| t.to_string()).join(" | ")),
+            Type::Tuple(types) => write!(f, "({})", types.iter().map(|t| t.to_string()).join(", ")),
+            Type::Struct(structure) => write!(f, "{}", structure.name),
+            Type::Enum(enumeration) => write!(f, "{}", enumeration.name),
+            Type::Nominal(nominal) => write!(f, "{}", nominal.name),
+            Type::Param(name) => write!(f, "{name}"),
+            Type::Function(function) => {
+                let args = function.args.iter().map(|t| t.to_string()).join(", ");
+                match *function.returns {
+                    Type::Null => write!(f, "Fun({args})"),
+                    ref returns => write!(f, "Fun({args}): {returns}"),
+                }
+            }
             Type::Generic => write!(f, "Generic"),
         }
     }
@@ -143,8 +364,11 @@ pub trait Typed {
 // Tries to parse the type - if it fails, it fails loudly
 pub fn parse_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
     let tok = meta.get_current_token();
-    try_parse_type(meta).map_err(|_| {
-        Failure::Loud(Message::new_err_at_token(meta, tok).message("Expected a data type"))
+    try_parse_type(meta).map_err(|err| match err {
+        Failure::Loud(_) => err,
+        Failure::Quiet(_) => {
+            Failure::Loud(Message::new_err_at_token(meta, tok).message("Expected a data type"))
+        }
     })
 }
 
@@ -200,6 +424,14 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
                     meta.increment_index();
                     Ok(Type::Null)
                 }
+                "Job" => {
+                    meta.increment_index();
+                    Ok(Type::job())
+                }
+                "Duration" => {
+                    meta.increment_index();
+                    Ok(Type::duration())
+                }
                 "[" => {
                     let index = meta.get_index();
                     meta.increment_index();
@@ -207,13 +439,21 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
                         Ok(Type::Array(Box::new(Type::Generic)))
                     } else {
                         match try_parse_type(meta) {
-                            Ok(Type::Array(_)) => error!(
-                                meta,
-                                tok, "Arrays cannot be nested due to the Bash limitations"
-                            ),
+                            Ok(kind @ Type::Array(_)) if kind.packed_depth() > MAX_PACKED_DEPTH => {
+                                error!(meta, tok => {
+                                    message: "Array is nested too deeply",
+                                    comment: format!("Arrays can be nested at most {MAX_PACKED_DEPTH} levels deep")
+                                })
+                            }
+                            Ok(Type::Map(_)) => {
+                                error!(meta, tok, "Arrays cannot contain maps")
+                            }
                             Ok(Type::Union(_)) => {
                                 error!(meta, tok, "Arrays don't support mixed type values")
                             }
+                            Ok(key_type) if token(meta, ":").is_ok() => {
+                                try_parse_map_type(meta, tok, key_type)
+                            }
                             Ok(result_type) => {
                                 token(meta, "]")?;
                                 Ok(Type::Array(Box::new(result_type)))
@@ -252,6 +492,20 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
                         comment: "Where 'T' is the type of the array elements"
                     })
                 }
+                "Fun" => {
+                    meta.increment_index();
+                    try_parse_function_type(meta)
+                }
+                "(" => try_parse_tuple_type(meta),
+                name if meta.context.type_params.contains_key(name) => {
+                    meta.increment_index();
+                    Ok(Type::Param(name.to_string()))
+                }
+                // User defined types are resolved in the typecheck phase
+                name if is_type_name(name) => {
+                    meta.increment_index();
+                    Ok(Type::Struct(StructType::unresolved(name)))
+                }
                 // The quiet error
                 _ => Err(Failure::Quiet(PositionInfo::at_eof(meta))),
             }
@@ -262,6 +516,163 @@ fn try_parse_simple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
     res
 }
 
+// Parses the tuple type `(T, U)` - if the contents are not types, it fails quietly
+fn try_parse_tuple_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
+    let index = meta.get_index();
+    let tok = meta.get_current_token();
+    token(meta, "(")?;
+    let mut types = vec![];
+    loop {
+        match try_parse_type(meta) {
+            Ok(kind) => types.push(kind),
+            Err(Failure::Quiet(pos)) => {
+                meta.set_index(index);
+                return Err(Failure::Quiet(pos));
+            }
+            Err(failure) => return Err(failure),
+        }
+        if token(meta, ")").is_ok() {
+            break;
+        }
+        if token(meta, ",").is_err() {
+            meta.set_index(index);
+            return Err(Failure::Quiet(PositionInfo::at_eof(meta)));
+        }
+    }
+    if types.len() < 2 {
+        return error!(meta, tok => {
+            message: "Tuples have to have at least two elements",
+            comment: "Use the type of the element instead"
+        });
+    }
+    Ok(Type::Tuple(types))
+}
+
+// Parses the signature of the function type `Fun(T, U): R`
+fn try_parse_function_type(meta: &mut ParserMetadata) -> Result<Type, Failure> {
+    token(meta, "(")?;
+    let mut args = vec![];
+    if token(meta, ")").is_err() {
+        loop {
+            args.push(parse_type(meta)?);
+            if token(meta, ")").is_ok() {
+                break;
+            }
+            token(meta, ",")?;
+        }
+    }
+    // Functions without the return type return nothing
+    let returns = match token(meta, ":") {
+        Ok(_) => try_parse_simple_type(meta)?,
+        Err(_) => Type::Null,
+    };
+    Ok(Type::Function(FunctionType {
+        args,
+        returns: Box::new(returns),
+    }))
+}
+
+// Parses the value type of the map type `[Text: T]`
+fn try_parse_map_type(
+    meta: &mut ParserMetadata,
+    tok: Option<Token>,
+    key_type: Type,
+) -> Result<Type, Failure> {
+    if key_type != Type::Text {
+        return error!(meta, tok => {
+            message: format!("Map keys cannot be of type '{key_type}'"),
+            comment: "Map keys have to be of type 'Text'"
+        });
+    }
+    let value_type = parse_type(meta)?;
+    if !is_map_value_type(&value_type) {
+        return error!(
+            meta,
+            tok,
+            format!("Map values cannot be of type '{value_type}'")
+        );
+    }
+    token(meta, "]")?;
+    meta.use_map(PositionInfo::from_token(meta, tok));
+    Ok(Type::map_of(value_type))
+}
+
+/// Determines if the values of given type can be stored in a map
+pub fn is_map_value_type(kind: &Type) -> bool {
+    matches!(
+        kind,
+        Type::Text
+            | Type::Int
+            | Type::Num
+            | Type::Bool
+            | Type::Struct(_)
+            | Type::Tuple(_)
+            | Type::Enum(_)
+    )
+}
+
+/// Determines if the identifier can name a user defined type (PascalCase)
+pub fn is_type_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+        && !is_all_caps(name)
+}
+
+/// Replaces unresolved user defined types with their declarations
+pub fn resolve_type(
+    meta: &mut ParserMetadata,
+    kind: &Type,
+    tok: Option<Token>,
+) -> Result<Type, Failure> {
+    match kind {
+        Type::Struct(structure) if !structure.is_resolved() => {
+            match meta.get_type_declaration(&structure.name) {
+                Some(decl) => Ok(decl.kind.clone()),
+                None => error!(
+                    meta,
+                    tok,
+                    format!("Type '{}' is not defined", structure.name)
+                ),
+            }
+        }
+        Type::Param(name) => Ok(meta
+            .context
+            .type_params
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| kind.clone())),
+        Type::Array(inner) => Ok(Type::array_of(resolve_type(meta, inner, tok)?)),
+        Type::Map(inner) => Ok(Type::map_of(resolve_type(meta, inner, tok)?)),
+        Type::Function(function) => {
+            let args = function
+                .args
+                .iter()
+                .map(|kind| resolve_type(meta, kind, tok.clone()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let returns = resolve_type(meta, &function.returns, tok)?;
+            Ok(Type::Function(FunctionType {
+                args,
+                returns: Box::new(returns),
+            }))
+        }
+        Type::Union(types) => {
+            let types = types
+                .iter()
+                .map(|kind| resolve_type(meta, kind, tok.clone()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Type::Union(types))
+        }
+        Type::Tuple(types) => {
+            let types = types
+                .iter()
+                .map(|kind| resolve_type(meta, kind, tok.clone()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Type::Tuple(types))
+        }
+        kind => Ok(kind.clone()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Type;
@@ -1,3 +1,4 @@
+use crate::modules::function::reference::FunctionReference;
 use crate::modules::prelude::*;
 use crate::modules::typecheck::TypeCheckModule;
 use crate::modules::types::{Type, Typed};
@@ -11,6 +12,8 @@ pub struct VariableGet {
     global_id: Option<usize>,
     is_ref: bool,
     tok: Option<Token>,
+    /// Set when the name refers to a function used as a value
+    function: Option<FunctionReference>,
 }
 
 impl Typed for VariableGet {
@@ -20,9 +23,22 @@ impl Typed for VariableGet {
 }
 
 impl VariableGet {
+    /// Creates an access of the variable with given name
+    pub fn from_name(name: &str, tok: Option<Token>) -> Self {
+        VariableGet {
+            name: name.to_string(),
+            tok,
+            ..VariableGet::new()
+        }
+    }
+
     pub fn is_variable_modified(&self) -> bool {
         false
     }
+
+    pub fn is_function(&self) -> bool {
+        self.function.is_some()
+    }
 }
 
 impl SyntaxModule<ParserMetadata> for VariableGet {
@@ -35,6 +51,7 @@ impl SyntaxModule<ParserMetadata> for VariableGet {
             global_id: None,
             is_ref: false,
             tok: None,
+            function: None,
         }
     }
 
@@ -47,6 +64,14 @@ impl SyntaxModule<ParserMetadata> for VariableGet {
 
 impl TypeCheckModule for VariableGet {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
+        if meta.get_var(&self.name).is_none() {
+            if let Some(fun) = meta.get_fun_declaration(&self.name).cloned() {
+                let function = FunctionReference::new(meta, self.tok.clone(), fun)?;
+                self.kind = function.get_type();
+                self.function = Some(function);
+                return Ok(());
+            }
+        }
         let variable = handle_variable_reference(meta, &self.tok, &self.name)?;
         self.global_id = variable.global_id;
         self.is_ref = variable.is_ref;
@@ -56,10 +81,14 @@ impl TypeCheckModule for VariableGet {
 }
 
 impl TranslateModule for VariableGet {
-    fn translate(&self, _meta: &mut TranslateMetadata) -> FragmentKind {
+    fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
+        if let Some(function) = &self.function {
+            return function.translate(meta);
+        }
         VarExprFragment::new(&self.name, self.get_type())
             .with_global_id(self.global_id)
             .with_ref(self.is_ref)
+            .with_local(meta.fun_meta.is_some())
             .to_frag()
     }
 }
@@ -7,6 +7,7 @@ use crate::modules::types::{Type, Typed};
 use crate::modules::{handle_symbol_scope_declaration, prelude::*};
 use crate::raw_fragment;
 use crate::translate::fragments::var_expr::{format_position, VarIndexValue};
+use crate::translate::packed::{get_packed_element, unpack_value};
 use crate::utils::cc_flags::{get_ccflag_by_name, get_ccflag_name, CCFlags};
 use crate::utils::context::{VariableDecl, VariableDeclWarn};
 use crate::utils::metadata::ParserMetadata;
@@ -101,8 +102,8 @@ impl TypeCheckModule for VariableInitDestruct {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         self.expr.typecheck(meta)?;
 
-        // Ensure the expression is an array of known type
-        let inner_type = match self.expr.get_type() {
+        // Ensure the expression is an array of known type or a tuple
+        let kinds = match self.expr.get_type() {
             Type::Array(inner) if *inner == Type::Generic => {
                 let pos = self.expr.get_position();
                 return error_pos!(meta, pos => {
@@ -110,24 +111,46 @@ impl TypeCheckModule for VariableInitDestruct {
                     comment: "Please add an explicit type annotation to this array value before destructuring"
                 });
             }
-            Type::Array(inner) => *inner,
+            Type::Array(inner) => {
+                // Elements of nested arrays are unpacked when assigned
+                if inner.is_array() {
+                    meta.packed_used = true;
+                }
+                vec![*inner; self.names.len()]
+            }
+            Type::Tuple(kinds) => {
+                if kinds.len() != self.names.len() {
+                    let pos = self.expr.get_position();
+                    return error_pos!(
+                        meta,
+                        pos,
+                        format!(
+                            "Cannot destructure tuple of {} elements into {} variables",
+                            kinds.len(),
+                            self.names.len()
+                        )
+                    );
+                }
+                meta.packed_used = true;
+                kinds
+            }
             _ => {
                 let pos = self.expr.get_position();
                 return error_pos!(
                     meta,
                     pos,
                     format!(
-                        "Destructuring initialization requires an array type, but received '{}'",
+                        "Destructuring initialization requires an array or tuple type, but received '{}'",
                         self.expr.get_type()
                     )
                 );
             }
         };
 
-        for (name, tok) in self.names.iter().zip(self.toks.iter()) {
+        for ((name, tok), kind) in self.names.iter().zip(self.toks.iter()).zip(kinds) {
             handle_identifier_name(meta, name, tok.clone())?;
             handle_symbol_scope_declaration(meta, name, tok.clone())?;
-            let var = VariableDecl::new(name.clone(), inner_type.clone())
+            let var = VariableDecl::new(name.clone(), kind)
                 .with_warn(
                     VariableDeclWarn::from_token(meta, tok.clone())
                         .warn_when_unmodified(!self.is_const && !meta.is_global_scope())
@@ -148,34 +171,50 @@ impl TranslateModule for VariableInitDestruct {
 
         let mut fragments = vec![];
 
-        // Assign expression to temp array
+        // Assign expression to temp variable before the elements are unpacked
         let temp_array_name = format!("array_destruct_{}", meta.gen_value_id());
         let assign_temp = VarStmtFragment::new(&temp_array_name, self.expr.get_type(), expr)
             .with_local(self.is_fun_ctx)
             .with_optimization_when_unused(false);
-        fragments.push(assign_temp.clone().to_frag());
-
-        let inner_type = match self.expr.get_type() {
-            Type::Array(t) => *t,
-            _ => unreachable!("Type of expression is not an array in init destructuring"),
-        };
+        meta.stmt_queue.push_back(assign_temp.clone().to_frag());
 
         for (i, name) in self.names.iter().enumerate() {
-            let token = self.toks[i].clone().unwrap();
-            let var_position = PositionInfo::at_pos(
-                self.expr.get_position().path,
-                token.pos,
-                token.start,
-                token.word.chars().count(),
-            );
-            let position = format_position(Some(&var_position));
-
-            let assign_expr = VarExprFragment::from_stmt(&assign_temp)
-                .with_index_by_value(VarIndexValue::Index(raw_fragment!("{i}")))
-                .with_index_pos(position)
-                .to_frag();
-
-            let assign_var = VarStmtFragment::new(name, inner_type.clone(), assign_expr)
+            let (assign_expr, kind) = match self.expr.get_type() {
+                // Tuple elements are stored as packed strings
+                Type::Tuple(kinds) => {
+                    let tuple = VarExprFragment::from_stmt(&assign_temp).to_frag();
+                    let element =
+                        get_packed_element(meta, tuple, i, self.expr.get_type().packed_depth());
+                    (unpack_value(meta, element, &kinds[i]), kinds[i].clone())
+                }
+                Type::Array(inner_type) => {
+                    let token = self.toks[i].clone().unwrap();
+                    let var_position = PositionInfo::at_pos(
+                        self.expr.get_position().path,
+                        token.pos,
+                        token.start,
+                        token.word.chars().count(),
+                    );
+                    let position = format_position(Some(&var_position));
+
+                    let mut assign_expr = VarExprFragment::from_stmt(&assign_temp)
+                        .with_index_by_value(VarIndexValue::Index(raw_fragment!("{i}")))
+                        .with_index_pos(position);
+                    // Elements of nested arrays are stored as packed strings
+                    let assign_expr = if inner_type.is_array() {
+                        assign_expr.kind = Type::Text;
+                        unpack_value(meta, assign_expr.to_frag(), &inner_type)
+                    } else {
+                        assign_expr.to_frag()
+                    };
+                    (assign_expr, *inner_type)
+                }
+                _ => unreachable!(
+                    "Type of expression is not an array or tuple in init destructuring"
+                ),
+            };
+
+            let assign_var = VarStmtFragment::new(name, kind, assign_expr)
                 .with_global_id(self.global_ids[i])
                 .with_local(self.is_fun_ctx)
                 .to_frag();
@@ -26,11 +26,14 @@ pub fn variable_name_keywords() -> Vec<&'static str> {
         "and",
         "as",
         "break",
+        "capture",
         "cd",
         "const",
         "continue",
+        "defer",
         "echo",
         "else",
+        "enum",
         "exit",
         "exited",
         "fail",
@@ -49,25 +52,33 @@ pub fn variable_name_keywords() -> Vec<&'static str> {
         "lock",
         "loop",
         "main",
+        "match",
         "mv",
         "nameof",
         "touch",
         "not",
         "null",
         "or",
+        "parallel",
         "pub",
         "ref",
         "return",
         "silent",
         "sleep",
+        "spawn",
         "status",
+        "struct",
         "sudo",
         "succeeded",
         "suppress",
         "then",
+        "timeout",
         "trust",
         "true",
+        "try",
+        "type",
         "unsafe",
+        "unset",
         "while",
     ]
 }
@@ -150,6 +161,21 @@ pub fn prevent_constant_mutation(
     }
 }
 
+pub fn prevent_job_mutation(
+    meta: &mut ParserMetadata,
+    tok: &Option<Token>,
+    name: &str,
+) -> SyntaxResult {
+    if meta.is_var_outside_job(name) {
+        error!(meta, tok.clone() => {
+            message: format!("Cannot modify variable '{name}' declared outside of the background job"),
+            comment: "The job runs in a subshell, so the change would be lost once it finishes"
+        })
+    } else {
+        Ok(())
+    }
+}
+
 fn handle_similar_variable(meta: &ParserMetadata, name: &str) -> Option<String> {
     let vars = Vec::from_iter(meta.get_var_names());
     find_best_similarity(name, &vars).and_then(|(match_name, score)| {
@@ -1,9 +1,10 @@
 use super::{
     handle_index_accessor, handle_variable_reference, prevent_constant_mutation,
-    validate_index_accessor, variable_name_extensions,
+    prevent_job_mutation, validate_index_accessor, variable_name_extensions,
 };
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
+use crate::translate::packed::pack_value;
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use crate::{modules::expression::expr::Expr, translate::module::TranslateModule};
 use heraclitus_compiler::prelude::*;
@@ -57,8 +58,29 @@ impl TypeCheckModule for VariableSet {
         self.is_ref = variable.is_ref;
         self.var_type = variable.kind.clone();
         prevent_constant_mutation(meta, &self.tok, &self.name, variable.is_const)?;
+        prevent_job_mutation(meta, &self.tok, &self.name)?;
         meta.mark_var_modified(&self.name);
 
+        if let (Some(index_expr), Type::Map(kind)) = (&self.index, &variable.kind) {
+            if index_expr.get_type() != Type::Text {
+                let pos = index_expr.get_position();
+                return error_pos!(meta, pos => {
+                    message: "Map keys have to be of type 'Text'",
+                    comment: format!("Given key is of type '{}'", index_expr.get_type())
+                });
+            }
+            let expr_type = self.expr.get_type();
+            if !expr_type.is_allowed_in(kind) {
+                let pos = self.expr.get_position();
+                return error_pos!(
+                    meta,
+                    pos,
+                    format!("Cannot assign value of type '{expr_type}' to a map of '{kind}'")
+                );
+            }
+            return Ok(());
+        }
+
         if let Some(ref index_expr) = self.index {
             if !matches!(variable.kind, Type::Array(_)) {
                 let left_type = variable.kind.clone();
@@ -102,6 +124,10 @@ impl TypeCheckModule for VariableSet {
                         )
                     );
                 }
+                // Elements of nested arrays are stored as packed strings
+                if kind.is_array() {
+                    meta.packed_used = true;
+                }
             }
         } else {
             // Check for type inference
@@ -135,10 +161,21 @@ impl TranslateModule for VariableSet {
     fn translate(&self, meta: &mut TranslateMetadata) -> FragmentKind {
         let index = self.index.as_ref().map(|v| v.translate(meta));
         let expr = self.expr.translate(meta);
-        VarStmtFragment::new(&self.name, self.expr.get_type(), expr)
+        let kind = self.expr.get_type();
+        // Elements of nested arrays are stored as packed strings
+        if index.is_some() && self.var_type.is_array() && kind.is_array() {
+            let expr = pack_value(meta, expr, &kind);
+            return VarStmtFragment::new(&self.name, Type::Text, expr)
+                .with_global_id(self.global_id)
+                .with_ref(self.is_ref)
+                .with_index(index)
+                .to_frag();
+        }
+        VarStmtFragment::new(&self.name, kind, expr)
             .with_global_id(self.global_id)
             .with_ref(self.is_ref)
             .with_index(index)
+            .with_reassignment(self.var_type.is_map())
             .to_frag()
     }
 }
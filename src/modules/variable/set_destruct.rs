@@ -1,8 +1,12 @@
-use super::{handle_variable_reference, prevent_constant_mutation, variable_name_extensions};
+use super::{
+    handle_variable_reference, prevent_constant_mutation, prevent_job_mutation,
+    variable_name_extensions,
+};
 use crate::modules::prelude::*;
 use crate::modules::types::{Type, Typed};
 use crate::raw_fragment;
 use crate::translate::fragments::var_expr::{format_position, VarIndexValue};
+use crate::translate::packed::{get_packed_element, unpack_value};
 use crate::utils::{ParserMetadata, TranslateMetadata};
 use crate::{modules::expression::expr::Expr, translate::module::TranslateModule};
 use heraclitus_compiler::prelude::*;
@@ -54,8 +58,8 @@ impl TypeCheckModule for VariableSetDestruct {
     fn typecheck(&mut self, meta: &mut ParserMetadata) -> SyntaxResult {
         self.expr.typecheck(meta)?;
 
-        // Ensure the expression is an array of known type
-        let inner_expr_type = match self.expr.get_type() {
+        // Ensure the expression is an array of known type or a tuple
+        let kinds = match self.expr.get_type() {
             Type::Array(inner) if *inner == Type::Generic => {
                 let pos = self.expr.get_position();
                 return error_pos!(meta, pos => {
@@ -63,29 +67,56 @@ impl TypeCheckModule for VariableSetDestruct {
                     comment: "Please add an explicit type annotation to this array value before destructuring"
                 });
             }
-            Type::Array(inner) => *inner,
+            Type::Array(inner) => {
+                // Elements of nested arrays are unpacked when assigned
+                if inner.is_array() {
+                    meta.packed_used = true;
+                }
+                vec![*inner; self.names.len()]
+            }
+            Type::Tuple(kinds) => {
+                if kinds.len() != self.names.len() {
+                    let pos = self.expr.get_position();
+                    return error_pos!(
+                        meta,
+                        pos,
+                        format!(
+                            "Cannot destructure tuple of {} elements into {} variables",
+                            kinds.len(),
+                            self.names.len()
+                        )
+                    );
+                }
+                meta.packed_used = true;
+                kinds
+            }
             _ => {
                 let pos = self.expr.get_position();
                 return error_pos!(
                     meta,
                     pos,
                     format!(
-                        "Destructuring assignment requires an array type, but received '{}'",
+                        "Destructuring assignment requires an array or tuple type, but received '{}'",
                         self.expr.get_type()
                     )
                 );
             }
         };
 
-        for (name, tok) in self.names.iter().zip(self.toks.iter()) {
+        for ((name, tok), inner_expr_type) in self.names.iter().zip(self.toks.iter()).zip(kinds) {
             let variable = handle_variable_reference(meta, tok, name)?;
             self.global_ids.push(variable.global_id);
             self.is_refs.push(variable.is_ref);
             self.var_types.push(variable.kind.clone());
 
             prevent_constant_mutation(meta, tok, name, variable.is_const)?;
+            prevent_job_mutation(meta, tok, name)?;
             meta.mark_var_modified(name);
 
+            // Elements of nested arrays are assigned as whole arrays
+            if inner_expr_type.is_array() && inner_expr_type.is_allowed_in(&variable.kind) {
+                continue;
+            }
             if let Type::Array(kind) = &variable.kind {
                 // Handle type inference for generic arrays or incompatible types
                 if **kind == Type::Generic {
@@ -123,34 +154,50 @@ impl TranslateModule for VariableSetDestruct {
         let expr = self.expr.translate(meta);
         let mut fragments = vec![];
 
-        // Assign expression to temp array
+        // Assign expression to temp variable before the elements are unpacked
         let temp_array_name = format!("array_destruct_{}", meta.gen_value_id());
         let assign_temp = VarStmtFragment::new(&temp_array_name, self.expr.get_type(), expr)
             .with_local(false)
             .with_optimization_when_unused(false);
-        fragments.push(assign_temp.clone().to_frag());
-
-        let inner_type = match self.expr.get_type() {
-            Type::Array(t) => *t,
-            _ => unreachable!("Type of expression is not an array in set destructuring"),
-        };
+        meta.stmt_queue.push_back(assign_temp.clone().to_frag());
 
         for (i, name) in self.names.iter().enumerate() {
-            let token = self.toks[i].clone().unwrap();
-            let var_position = PositionInfo::at_pos(
-                self.expr.get_position().path,
-                token.pos,
-                token.start,
-                token.word.chars().count(),
-            );
-            let position = format_position(Some(&var_position));
-
-            let assign_expr = VarExprFragment::from_stmt(&assign_temp)
-                .with_index_by_value(VarIndexValue::Index(raw_fragment!("{i}")))
-                .with_index_pos(position)
-                .to_frag();
+            let (assign_expr, kind) = match self.expr.get_type() {
+                // Tuple elements are stored as packed strings
+                Type::Tuple(kinds) => {
+                    let tuple = VarExprFragment::from_stmt(&assign_temp).to_frag();
+                    let element =
+                        get_packed_element(meta, tuple, i, self.expr.get_type().packed_depth());
+                    (unpack_value(meta, element, &kinds[i]), kinds[i].clone())
+                }
+                Type::Array(inner_type) => {
+                    let token = self.toks[i].clone().unwrap();
+                    let var_position = PositionInfo::at_pos(
+                        self.expr.get_position().path,
+                        token.pos,
+                        token.start,
+                        token.word.chars().count(),
+                    );
+                    let position = format_position(Some(&var_position));
+
+                    let mut assign_expr = VarExprFragment::from_stmt(&assign_temp)
+                        .with_index_by_value(VarIndexValue::Index(raw_fragment!("{i}")))
+                        .with_index_pos(position);
+                    // Elements of nested arrays are stored as packed strings
+                    let assign_expr = if inner_type.is_array() {
+                        assign_expr.kind = Type::Text;
+                        unpack_value(meta, assign_expr.to_frag(), &inner_type)
+                    } else {
+                        assign_expr.to_frag()
+                    };
+                    (assign_expr, *inner_type)
+                }
+                _ => {
+                    unreachable!("Type of expression is not an array or tuple in set destructuring")
+                }
+            };
 
-            let assign_var = VarStmtFragment::new(name, inner_type.clone(), assign_expr)
+            let assign_var = VarStmtFragment::new(name, kind, assign_expr)
                 .with_global_id(self.global_ids[i])
                 .with_ref(self.is_refs[i])
                 .to_frag();
@@ -113,6 +113,18 @@ pub fn remove_unused_variables(ast: &mut FragmentKind) {
 }
 
 fn remove_non_existing_variables(ast: &mut FragmentKind, meta: &mut UnusedVariablesMetadata) {
+    // Variable statements inside of lists are kept but their symbols are skipped,
+    // so that the statements that follow are not matched with them
+    if let FragmentKind::List(list) = ast {
+        for item in list.values.iter_mut() {
+            match item {
+                FragmentKind::VarStmt(var_stmt) if should_optimize_var_stmt(var_stmt) => {
+                    meta.move_to_var_stmt_init(&var_stmt.get_name());
+                }
+                _ => remove_non_existing_variables(item, meta),
+            }
+        }
+    }
     if let FragmentKind::Block(block) = ast {
         let mut remove_indexes = vec![];
         for (index, statement) in block.statements.iter_mut().enumerate() {
@@ -205,8 +217,10 @@ fn find_unused_variables(ast: &FragmentKind, meta: &mut UnusedVariablesMetadata)
             }
             if let Some(index) = &var_expr.index {
                 match index.as_ref() {
-                    VarIndexValue::Index(index) => find_unused_variables(index, meta),
-                    VarIndexValue::Range(start, end) => {
+                    VarIndexValue::Index(index) | VarIndexValue::Key(index) => {
+                        find_unused_variables(index, meta)
+                    }
+                    VarIndexValue::Range(start, end) | VarIndexValue::Substring(start, end) => {
                         find_unused_variables(start, meta);
                         find_unused_variables(end, meta);
                     }
@@ -0,0 +1 @@
+__sep_1=$'\x1f' __sep_2=$'\x1e' __sep_3=$'\x1d' __sep_4=$'\x1c'
@@ -1,5 +1,16 @@
 use heraclitus_compiler::prelude::*;
 
+/// Replaces the last quote of the opening delimiters of multiline and raw text literals
+pub const LITERAL_MARKER: char = '\u{1}';
+/// Opening delimiter of a multiline text literal once it is marked in the code
+pub const MULTILINE_BEGIN: &str = "\"\"\u{1}";
+/// Opening delimiter of a raw multiline text literal once it is marked in the code
+pub const RAW_MULTILINE_BEGIN: &str = "r\"\"\u{1}";
+/// Opening delimiter of a raw text literal once it is marked in the code
+pub const RAW_BEGIN: &str = "r\u{1}";
+/// Delimiter of a multiline text literal as written in the code
+pub const MULTILINE_DELIMITER: &str = "\"\"\"";
+
 pub fn get_rules() -> Rules {
     let symbols = vec![
         '+', '-', '*', '/', '%', '\n', ';', ':', '(', ')', '[', ']', '{', '}', ',', '.', '<', '>',
@@ -17,8 +28,29 @@ pub fn get_rules() -> Rules {
         ('%', '='),
         ('.', '.'),
         ('/', '/'),
+        ('?', '?'),
     ];
     let region = reg![
+        reg!(raw_multiline_string as "raw multiline string literal" => {
+            begin: RAW_MULTILINE_BEGIN,
+            end: MULTILINE_DELIMITER,
+            ignore_escaped: true
+        }),
+        reg!(raw_string as "raw string literal" => {
+            begin: RAW_BEGIN,
+            end: "\"",
+            ignore_escaped: true
+        }),
+        reg!(multiline_string as "multiline string literal" => {
+            begin: MULTILINE_BEGIN,
+            end: MULTILINE_DELIMITER
+        } => [
+            reg!(str_interp as "string interpolation" => {
+                begin: "{",
+                end: "}",
+                tokenize: true
+            } ref global)
+        ]),
         reg!(string as "string literal" => {
             begin: "\"",
             end: "\""
@@ -57,3 +89,93 @@ pub fn get_rules() -> Rules {
     ];
     Rules::new(symbols, compounds, region)
 }
+
+/// Scope of the code that is being scanned for multiline text literals
+enum Scope {
+    Code,
+    Text,
+    Command,
+    Multiline,
+}
+
+/// Marks the opening delimiters of multiline and raw text literals.
+///
+/// The lexer closes a region as soon as its closing delimiter was read, which would
+/// happen inside of the opening `"""` or `r"`. The last quote of the opening delimiter
+/// is therefore replaced with `LITERAL_MARKER`, which keeps the positions in the code.
+pub fn mark_text_literals(code: &str) -> String {
+    let delimiter: Vec<char> = MULTILINE_DELIMITER.chars().collect();
+    let mut chars: Vec<char> = code.chars().collect();
+    let mut scopes = vec![Scope::Code];
+    let mut index = 0;
+    let starts_with = |chars: &[char], index: usize, pattern: &[char]| {
+        chars.get(index..index + pattern.len()) == Some(pattern)
+    };
+    let find_from = |chars: &[char], index: usize, pattern: &[char]| {
+        (index..chars.len()).find(|index| starts_with(chars, *index, pattern))
+    };
+    while index < chars.len() {
+        let letter = chars[index];
+        match scopes.last().unwrap() {
+            Scope::Code => {
+                let is_word_start =
+                    index == 0 || !(chars[index - 1].is_alphanumeric() || chars[index - 1] == '_');
+                if starts_with(&chars, index, &['/', '/']) {
+                    index = find_from(&chars, index, &['\n']).unwrap_or(chars.len());
+                    continue;
+                }
+                // Raw literals end with the first closing delimiter
+                if letter == 'r' && is_word_start && starts_with(&chars, index + 1, &delimiter) {
+                    chars[index + 3] = LITERAL_MARKER;
+                    index =
+                        find_from(&chars, index + 4, &delimiter).map_or(chars.len(), |end| end + 3);
+                    continue;
+                }
+                if letter == 'r' && is_word_start && chars.get(index + 1) == Some(&'"') {
+                    chars[index + 1] = LITERAL_MARKER;
+                    index = find_from(&chars, index + 2, &['"']).map_or(chars.len(), |end| end + 1);
+                    continue;
+                }
+                if starts_with(&chars, index, &delimiter) {
+                    chars[index + 2] = LITERAL_MARKER;
+                    scopes.push(Scope::Multiline);
+                    index += 3;
+                    continue;
+                }
+                match letter {
+                    '"' => scopes.push(Scope::Text),
+                    '$' => scopes.push(Scope::Command),
+                    '{' => scopes.push(Scope::Code),
+                    '}' if scopes.len() > 1 => {
+                        scopes.pop();
+                    }
+                    _ => {}
+                }
+            }
+            Scope::Text | Scope::Command | Scope::Multiline => match letter {
+                '\\' => index += 1,
+                '{' => scopes.push(Scope::Code),
+                '"' if matches!(scopes.last(), Some(Scope::Text)) => {
+                    scopes.pop();
+                }
+                '$' if matches!(scopes.last(), Some(Scope::Command)) => {
+                    scopes.pop();
+                }
+                '"' if matches!(scopes.last(), Some(Scope::Multiline))
+                    && starts_with(&chars, index, &delimiter) =>
+                {
+                    scopes.pop();
+                    index += 2;
+                }
+                _ => {}
+            },
+        }
+        index += 1;
+    }
+    chars.into_iter().collect()
+}
+
+/// Restores the opening delimiters of multiline and raw text literals
+pub fn unmark_text_literals(code: &str) -> String {
+    code.replace(LITERAL_MARKER, "\"")
+}
@@ -265,6 +265,54 @@ fn test_input_confirm_stdin() {
     assert!(stdout.contains("Continued"));
 }
 
+#[test]
+fn test_timeout_terminal_stdin() {
+    // Amber code reading the standard input under a timeout
+    let amber_code = r#"
+        main {
+            const line = timeout(5s) $ head -n 1 $ failed(code) {
+                echo("Timed out with {code}")
+            }
+            echo("Read {line}")
+        }
+        "#;
+
+    // Amber compiler setup and parse
+    let options = CompilerOptions::default();
+    let compiler = AmberCompiler::new(amber_code.to_string(), None, options);
+    let (messages, bash_code) = compiler.compile().unwrap();
+    assert_eq!(messages.len(), 0);
+
+    let script = NamedTempFile::new().expect("Failed to create script file");
+    std::fs::write(script.path(), bash_code).expect("Failed to write script file");
+
+    // Run the script in a terminal, so that a job that reads from it
+    // in the background would be stopped instead of reading the input
+    let mut child = std::process::Command::new("timeout")
+        .arg("20")
+        .arg("script")
+        .arg("-qec")
+        .arg(format!("bash {}", script.path().display()))
+        .arg("/dev/null")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn script");
+
+    {
+        use std::io::Write;
+        // We run this in a block to ensure stdin is closed after writing
+        let stdin = child.stdin.as_mut().expect("Failed to open stdin");
+        stdin.write_all(b"typed\n").expect("Failed to write to stdin");
+    }
+
+    let output = child.wait_with_output().expect("Failed to read stdout");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Read typed"));
+}
+
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::sync::OnceLock;
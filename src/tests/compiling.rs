@@ -705,3 +705,25 @@ fn test_shell_injection_backslash_handled() {
         stdout
     );
 }
+
+#[test]
+fn test_bash_32_rejects_maps() {
+    let code = r#"
+main {
+    const ages = ["alice": 30]
+    echo(ages["alice"])
+}
+"#;
+    let options = CompilerOptions::default().with_target(Some(ShellType::BashLegacy));
+    let compiler = AmberCompiler::new(code.to_string(), None, options);
+    let tokens = compiler.tokenize().expect("tokenize failed");
+    let (ast, meta) = compiler.parse(tokens).expect("parse failed");
+    let (ast, meta) = compiler.typecheck(ast, meta).expect("typecheck failed");
+    let error = compiler
+        .translate(ast, meta)
+        .expect_err("Maps should not compile for bash-3.2");
+    assert_eq!(
+        error.message.as_deref(),
+        Some("Maps are not supported by the 'bash-3.2' target")
+    );
+}
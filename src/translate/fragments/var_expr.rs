@@ -5,6 +5,7 @@ use crate::modules::expression::expr::{Expr, ExprType};
 use crate::modules::prelude::RawFragment;
 use crate::modules::prelude::*;
 use crate::modules::types::Type;
+use crate::translate::map::render_map_packing;
 use crate::utils::{ShellType, TranslateMetadata};
 use heraclitus_compiler::prelude::Position;
 use heraclitus_compiler::prelude::PositionInfo;
@@ -33,6 +34,10 @@ pub enum VarRenderType {
 pub enum VarIndexValue {
     Index(FragmentKind),
     Range(FragmentKind, FragmentKind),
+    // Offset and length of a substring
+    Substring(FragmentKind, FragmentKind),
+    // Key of a map
+    Key(FragmentKind),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,6 +59,8 @@ pub struct VarExprFragment {
     pub is_declared: bool,
     // Variable is inside an arithmetic expression
     pub is_math_var: bool,
+    // Temporaries used to pack a map value are declared local
+    pub is_local: bool,
     pub render_type: VarRenderType,
     // Amber's array subscript like `arr[0]` or `arr[1..5]`
     pub index: Option<Box<VarIndexValue>>,
@@ -73,6 +80,7 @@ impl Default for VarExprFragment {
             is_length: false,
             is_array_to_string: false,
             is_math_var: false,
+            is_local: false,
             is_quoted: true,
             is_array_ref: false,
             is_declared: true,
@@ -122,12 +130,18 @@ impl VarExprFragment {
         self
     }
 
+    pub fn with_local(mut self, is_local: bool) -> Self {
+        self.is_local = is_local;
+        self
+    }
+
     pub fn from_stmt(stmt: &VarStmtFragment) -> Self {
         VarExprFragment {
             name: stmt.name.clone(),
             global_id: stmt.global_id,
             kind: stmt.kind.clone(),
             is_ref: stmt.is_ref,
+            is_local: stmt.is_local,
             ..Default::default()
         }
     }
@@ -139,15 +153,29 @@ impl VarExprFragment {
     ) -> Self {
         if let Some(index) = index.into() {
             self.index_pos = format_position(index.position.as_ref());
+            let is_text = self.kind == Type::Text;
             let index = match index.value {
                 Some(ExprType::Range(range)) => {
-                    let (offset, length) = range.get_array_index(meta);
-                    VarIndexValue::Range(offset, length)
+                    let size = self.clone().with_length_getter(true).to_frag();
+                    let (offset, length) = range.get_array_index(meta, size, is_text);
+                    if is_text {
+                        VarIndexValue::Substring(offset, length)
+                    } else {
+                        VarIndexValue::Range(offset, length)
+                    }
+                }
+                Some(ExprType::Neg(neg)) if is_text => {
+                    let index = neg.get_array_index(meta);
+                    VarIndexValue::Substring(index, RawFragment::from("1".to_string()).to_frag())
                 }
                 Some(ExprType::Neg(neg)) => {
                     let index = neg.get_array_index(meta);
                     VarIndexValue::Index(index)
                 }
+                _ if is_text => {
+                    let index = index.translate_eval(meta, true);
+                    VarIndexValue::Substring(index, RawFragment::from("1".to_string()).to_frag())
+                }
                 _ => {
                     let index = index.translate_eval(meta, true);
                     VarIndexValue::Index(index)
@@ -191,6 +219,8 @@ impl VarExprFragment {
         match self.index.as_deref() {
             Some(VarIndexValue::Index(_)) => "index".to_string(),
             Some(VarIndexValue::Range(_, _)) => "range".to_string(),
+            Some(VarIndexValue::Substring(_, _)) => "substring".to_string(),
+            Some(VarIndexValue::Key(_)) => "key".to_string(),
             None => "access".to_string(),
         }
     }
@@ -225,6 +255,13 @@ impl VarExprFragment {
     // Returns the variable value in the bash/zsh/ksh context Ex. "$varname" or "${varname[@]}"
     pub fn render_variable_value(mut self, meta: &mut TranslateMetadata) -> String {
         let name = self.get_name();
+        // Maps used as values are packed into a single string
+        if self.kind.is_map() && self.index.is_none() && !self.is_length {
+            let packed = render_map_packing(meta, &name, &self.kind, self.is_local);
+            let quote = if self.is_quoted { meta.gen_quote() } else { "" };
+            let dollar = meta.gen_dollar();
+            return format!("{quote}{dollar}{{{packed}}}{quote}");
+        }
         let index = self.index.take();
         let index_is_none = index.is_none();
         let prefix = self.get_variable_prefix();
@@ -307,6 +344,12 @@ impl VarExprFragment {
                 };
                 format!("{slice}:{offset}:{length}")
             }
+            // Substring expansion counts negative offsets from the end of the text
+            (_, Some(VarIndexValue::Substring(offset, length))) => {
+                let offset = offset.with_quotes(false).to_string(meta);
+                let length = length.with_quotes(false).to_string(meta);
+                format!(": {offset}:{length}")
+            }
             (_, Some(VarIndexValue::Index(index))) => {
                 let index = index.with_quotes(false).to_string(meta);
                 let location = self.index_pos.as_deref().unwrap_or("unknown");
@@ -318,7 +361,7 @@ impl VarExprFragment {
                         let var_name = self.get_name();
                         meta.stmt_queue.push_back(
                             RawFragment::from(format!(
-                                "(( {index} >= 0 && {index} < ${{#{var_name}[@]}} )) || {{ echo \"Index out of bounds (at {location})\" >&2; exit 1; }}"
+                                "(( {index} >= -${{#{var_name}[@]}} && {index} < ${{#{var_name}[@]}} )) || {{ echo \"Index out of bounds (at {location})\" >&2; exit 1; }}"
                             ))
                             .to_frag(),
                         );
@@ -327,8 +370,27 @@ impl VarExprFragment {
                     _ => format!("[{index}]?\"Index out of bounds (at {location})\""),
                 }
             }
+            (_, Some(VarIndexValue::Key(key))) => {
+                let key = key.with_quotes(false).to_string(meta);
+                let location = self.index_pos.as_deref().unwrap_or("unknown");
+
+                match meta.target.shell {
+                    ShellType::Ksh => {
+                        // Same as with indexes, ksh does not fail on missing keys
+                        let var_name = self.get_name();
+                        meta.stmt_queue.push_back(
+                            RawFragment::from(format!(
+                                "[ -n \"${{{var_name}[{key}]+set}}\" ] || {{ echo \"Key not found (at {location})\" >&2; exit 1; }}"
+                            ))
+                            .to_frag(),
+                        );
+                        format!("[{key}]")
+                    }
+                    _ => format!("[{key}]?\"Key not found (at {location})\""),
+                }
+            }
             (Type::Array(_), None) if self.is_array_to_string => String::from("[*]"),
-            (Type::Array(_), None) => String::from("[@]"),
+            (Type::Array(_) | Type::Map(_), None) => String::from("[@]"),
             _ => String::new(),
         }
     }
@@ -1,6 +1,7 @@
 use crate::eval_context;
 use crate::modules::prelude::*;
 use crate::modules::types::Type;
+use crate::translate::map::render_map_assignment;
 use crate::utils::ShellType;
 
 use super::get_variable_name;
@@ -18,6 +19,8 @@ pub struct VarStmtFragment {
     // a "workaround" to properly generate array declaration by copying the origin array
     pub is_array_ref: bool,
     pub is_declared: bool,
+    // Assigns to an existing variable that must not be declared again (used by maps)
+    pub is_reassignment: bool,
     // Determines if the variable can be removed when not used
     pub optimize_unused: bool,
     pub operator: String,
@@ -38,6 +41,7 @@ impl Default for VarStmtFragment {
             is_local: false,
             is_array_ref: false,
             is_declared: true,
+            is_reassignment: false,
             optimize_unused: true,
             operator: "=".to_string(),
             value: Box::new(FragmentKind::Empty),
@@ -85,6 +89,11 @@ impl VarStmtFragment {
         self
     }
 
+    pub fn with_reassignment(mut self, is_reassignment: bool) -> Self {
+        self.is_reassignment = is_reassignment;
+        self
+    }
+
     pub fn with_index<T: Into<Option<FragmentKind>>>(mut self, index: T) -> Self {
         self.index = index.into().map(Box::new);
         self
@@ -192,6 +201,19 @@ impl VarStmtFragment {
 
 impl FragmentRenderable for VarStmtFragment {
     fn to_string(self, meta: &mut TranslateMetadata) -> String {
+        // Maps are stored in associative arrays filled from a packed value
+        if self.kind.is_map() && self.index.is_none() {
+            let name = self.get_name();
+            let value = self.value.to_string(meta);
+            return render_map_assignment(
+                meta,
+                &name,
+                &self.kind,
+                value,
+                self.is_local,
+                self.is_reassignment,
+            );
+        }
         match meta.target.shell {
             // if array is a direct reference and is already declared, use eval to modify directly
             ShellType::BashLegacy | ShellType::Zsh => {
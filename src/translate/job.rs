@@ -0,0 +1,66 @@
+use crate::fragments;
+use crate::modules::prelude::*;
+use crate::modules::types::Type;
+use crate::raw_fragment;
+
+// Background jobs that can be killed run in their own process groups.
+// Killing the whole group also stops the processes that the job started
+// without relying on external tools to find them. The monitor mode that
+// creates the group is only turned on for the job itself, so the job control
+// of the script stays as it was.
+
+/// Starts the translated job in the background in its own process group.
+/// The job can span multiple lines, such as a subshell running a block.
+pub fn translate_job_start(
+    meta: &mut TranslateMetadata,
+    job: Vec<FragmentKind>,
+) -> Vec<FragmentKind> {
+    start_job(meta, job, "set -m; ")
+}
+
+/// Starts the translated job that reads the standard input of the script in the background.
+/// A job in its own process group stops once it reads from the terminal,
+/// so it only gets one when the standard input is not a terminal.
+pub fn translate_input_job_start(
+    meta: &mut TranslateMetadata,
+    job: Vec<FragmentKind>,
+) -> Vec<FragmentKind> {
+    start_job(meta, job, "[ -t 0 ] || set -m; ")
+}
+
+fn start_job(
+    meta: &mut TranslateMetadata,
+    mut job: Vec<FragmentKind>,
+    monitor: &str,
+) -> Vec<FragmentKind> {
+    let is_local = meta.fun_meta.is_some();
+    let options = VarStmtFragment::new("__options", Type::Text, raw_fragment!("$-"))
+        .with_global_id(meta.gen_value_id())
+        .with_local(is_local)
+        .with_optimization_when_unused(false);
+    let options_expr = VarExprFragment::from_stmt(&options).to_frag();
+    if let Some(first) = job.first_mut() {
+        *first = fragments!(raw_fragment!("{monitor}"), std::mem::take(first));
+    }
+    if let Some(last) = job.last_mut() {
+        *last = fragments!(
+            std::mem::take(last),
+            " & case ",
+            options_expr,
+            " in *m*) ;; *) set +m ;; esac"
+        );
+    }
+    job.insert(0, options.to_frag());
+    job
+}
+
+/// Kills the process group of the job or the job alone when it does not lead a group
+pub fn translate_job_kill(pid: FragmentKind) -> FragmentKind {
+    fragments!(
+        "kill -- -",
+        pid.clone(),
+        " 2>/dev/null || kill ",
+        pid,
+        " 2>/dev/null"
+    )
+}
@@ -0,0 +1,129 @@
+use crate::modules::prelude::*;
+use crate::modules::types::Type;
+use crate::translate::fragments::var_expr::VarRenderType;
+use crate::translate::packed::get_separator_name;
+use crate::utils::ShellType;
+use crate::{fragments, raw_fragment};
+
+// Maps are stored in associative arrays, but whenever a map is used as a value
+// (passed to a function, returned, stored in a struct) it is packed into a single
+// string in which every key and every value is followed by a separator:
+//
+// key1 <sep> value1 <sep> key2 <sep> value2 <sep>
+//
+// The entries of a packed map are in no particular order.
+
+/// Returns the expression that expands to all keys of the map variable
+pub fn render_map_keys(meta: &TranslateMetadata, name: &str) -> String {
+    match meta.target.shell {
+        ShellType::Zsh => format!("\"${{(@k){name}}}\""),
+        _ => format!("\"${{!{name}[@]}}\""),
+    }
+}
+
+/// Renders the declaration of the map variable followed by the loop that fills it
+/// with the entries of a packed value
+pub fn render_map_assignment(
+    meta: &mut TranslateMetadata,
+    name: &str,
+    kind: &Type,
+    value: String,
+    is_local: bool,
+    is_reassignment: bool,
+) -> String {
+    let declaration = match (&meta.target.shell, is_local, is_reassignment) {
+        (_, _, true) => format!("{name}=()"),
+        (ShellType::Ksh, _, _) => format!("typeset -A {name}=()"),
+        (_, true, _) => format!("local -A {name}=()"),
+        (ShellType::Zsh, false, _) => format!("typeset -A {name}=()"),
+        (_, false, _) => format!("declare -A {name}=()"),
+    };
+    let sep = get_separator_name(kind.packed_depth());
+    let (packed_stmt, key_stmt) = render_map_temporaries(meta, value, is_local);
+    let packed = packed_stmt.get_name();
+    let key = key_stmt.get_name();
+    let indent = meta.gen_indent();
+    let mut lines = vec![declaration, indent.clone() + &packed_stmt.to_string(meta)];
+    if is_local {
+        lines.push(indent.clone() + &key_stmt.to_string(meta));
+    }
+    lines.push(format!(
+            "{indent}while [ -n \"${{{packed}}}\" ]; do {key}=\"${{{packed}%%\"${{{sep}}}\"*}}\"; {packed}=\"${{{packed}#*\"${{{sep}}}\"}}\"; {name}[${{{key}}}]=\"${{{packed}%%\"${{{sep}}}\"*}}\"; {packed}=\"${{{packed}#*\"${{{sep}}}\"}}\"; done"
+    ));
+    lines.join("\n")
+}
+
+/// Creates the statements of the packed value and the key used to traverse a map.
+/// Inside of functions they are local so that they don't leak into the caller.
+fn render_map_temporaries(
+    meta: &mut TranslateMetadata,
+    value: String,
+    is_local: bool,
+) -> (VarStmtFragment, VarStmtFragment) {
+    let id = meta.gen_value_id();
+    let temporary = |name: &str, value: String| {
+        VarStmtFragment::new(name, Type::Text, RawFragment::from(value).to_frag())
+            .with_global_id(id)
+            .with_local(is_local)
+            .with_optimization_when_unused(false)
+    };
+    (
+        temporary("__packed", value),
+        temporary("__key", String::new()),
+    )
+}
+
+/// Packs the entries of the map variable into a new variable and returns its name
+pub fn render_map_packing(
+    meta: &mut TranslateMetadata,
+    name: &str,
+    kind: &Type,
+    is_local: bool,
+) -> String {
+    let sep = get_separator_name(kind.packed_depth());
+    let (packed_stmt, key_stmt) = render_map_temporaries(meta, "\"\"".to_string(), is_local);
+    let packed = packed_stmt.get_name();
+    let key = key_stmt.get_name();
+    let keys = render_map_keys(meta, name);
+    meta.stmt_queue.push_back(packed_stmt.to_frag());
+    if is_local {
+        meta.stmt_queue.push_back(key_stmt.to_frag());
+    }
+    meta.stmt_queue.push_back(raw_fragment!(
+        "for {key} in {keys}; do {packed}+=\"${{{key}}}${{{sep}}}${{{name}[${{{key}}}]}}${{{sep}}}\"; done"
+    ));
+    packed
+}
+
+/// Returns the map variable holding given value. Values that are not
+/// stored in a map variable yet are unpacked into a temporary one.
+pub fn get_map_variable(
+    meta: &mut TranslateMetadata,
+    value: FragmentKind,
+    kind: &Type,
+) -> VarExprFragment {
+    match value {
+        FragmentKind::VarExpr(var) if var.kind.is_map() && var.index.is_none() && !var.is_ref => {
+            var
+        }
+        value => {
+            let id = meta.gen_value_id();
+            let stmt = VarStmtFragment::new("__map", kind.clone(), value)
+                .with_global_id(id)
+                .with_optimization_when_unused(false);
+            meta.push_ephemeral_variable(stmt)
+        }
+    }
+}
+
+/// Returns the fragment that expands to all keys of the map variable
+pub fn get_map_keys(meta: &TranslateMetadata, map: &VarExprFragment) -> FragmentKind {
+    let name = map
+        .clone()
+        .with_render_type(VarRenderType::NameOf)
+        .to_frag();
+    match meta.target.shell {
+        ShellType::Zsh => fragments!("\"${(@k)", name, "}\""),
+        _ => fragments!("\"${!", name, "[@]}\""),
+    }
+}
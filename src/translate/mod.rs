@@ -4,7 +4,10 @@ use heraclitus_compiler::prelude::*;
 pub mod compare;
 pub mod compute;
 pub mod fragments;
+pub mod job;
+pub mod map;
 pub mod module;
+pub mod packed;
 
 pub fn check_all_blocks(meta: &ParserMetadata) -> SyntaxResult {
     let mut stack = 0;
@@ -0,0 +1,114 @@
+use crate::fragments;
+use crate::modules::prelude::*;
+use crate::modules::types::Type;
+use crate::raw_fragment;
+
+// Compound values that cannot be represented by a single shell variable
+// (structs, arrays stored inside of other values) are packed into a single string.
+// Every element is followed by a separator character. The separator is picked
+// by the nesting depth of the packed value so that the inner values never
+// contain the separator of the outer one:
+//
+// depth 1 -> \x1f, depth 2 -> \x1e, depth 3 -> \x1d, depth 4 -> \x1c
+
+/// Returns the name of the variable holding the separator for given depth
+pub fn get_separator_name(depth: usize) -> String {
+    format!("__sep_{depth}")
+}
+
+/// Returns the expression that expands to the separator for given depth
+pub fn get_separator(depth: usize) -> FragmentKind {
+    VarExprFragment::new(&get_separator_name(depth), Type::Text).to_frag()
+}
+
+/// Packs already translated scalar values into a single string
+pub fn pack_values(values: Vec<FragmentKind>, depth: usize) -> FragmentKind {
+    let parts = values
+        .into_iter()
+        .flat_map(|value| [value, get_separator(depth)])
+        .collect();
+    ListFragment::new(parts).to_frag()
+}
+
+/// Converts the value of given type so that it can be stored inside of a packed value
+pub fn pack_value(meta: &mut TranslateMetadata, value: FragmentKind, kind: &Type) -> FragmentKind {
+    match kind {
+        Type::Array(_) => pack_array(meta, value, kind),
+        _ => value,
+    }
+}
+
+/// Converts the value stored inside of a packed value back to the value of given type
+pub fn unpack_value(
+    meta: &mut TranslateMetadata,
+    value: FragmentKind,
+    kind: &Type,
+) -> FragmentKind {
+    match kind {
+        Type::Array(_) => unpack_array(meta, value, kind),
+        _ => value,
+    }
+}
+
+/// Joins elements of an array into a single packed string
+pub fn pack_array(meta: &mut TranslateMetadata, array: FragmentKind, kind: &Type) -> FragmentKind {
+    let sep = get_separator_name(kind.packed_depth());
+    let id = meta.gen_value_id();
+    let stmt = VarStmtFragment::new("__packed", Type::Text, raw_fragment!("\"\""))
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let packed = meta.push_ephemeral_variable(stmt);
+    let name = packed.get_name();
+    meta.stmt_queue.push_back(fragments!(
+        "for __item in ",
+        array,
+        raw_fragment!("; do {name}+=\"${{__item}}${{{sep}}}\"; done")
+    ));
+    packed.to_frag()
+}
+
+/// Splits a packed string back into an array
+pub fn unpack_array(
+    meta: &mut TranslateMetadata,
+    value: FragmentKind,
+    kind: &Type,
+) -> FragmentKind {
+    let sep = get_separator_name(kind.packed_depth());
+    let id = meta.gen_value_id();
+    let source = VarStmtFragment::new("__packed", Type::Text, value)
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let source = meta.push_ephemeral_variable(source).get_name();
+    let stmt = VarStmtFragment::new("__unpacked", kind.clone(), FragmentKind::Empty)
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let array = meta.push_ephemeral_variable(stmt);
+    let name = array.get_name();
+    meta.stmt_queue.push_back(raw_fragment!(
+        "while [ -n \"${{{source}}}\" ]; do {name}+=(\"${{{source}%%\"${{{sep}}}\"*}}\"); {source}=\"${{{source}#*\"${{{sep}}}\"}}\"; done"
+    ));
+    array.to_frag()
+}
+
+/// Extracts the element at given position from a packed string
+pub fn get_packed_element(
+    meta: &mut TranslateMetadata,
+    value: FragmentKind,
+    index: usize,
+    depth: usize,
+) -> FragmentKind {
+    let sep = get_separator_name(depth);
+    let id = meta.gen_value_id();
+    let stmt = VarStmtFragment::new("__element", Type::Text, value)
+        .with_global_id(id)
+        .with_optimization_when_unused(false);
+    let element = meta.push_ephemeral_variable(stmt);
+    let name = element.get_name();
+    for _ in 0..index {
+        meta.stmt_queue
+            .push_back(raw_fragment!("{name}=\"${{{name}#*\"${{{sep}}}\"}}\""));
+    }
+    meta.stmt_queue
+        .push_back(raw_fragment!("{name}=\"${{{name}%%\"${{{sep}}}\"*}}\""));
+    element.to_frag()
+}
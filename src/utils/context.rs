@@ -12,6 +12,7 @@ pub struct FunctionDeclArg {
     pub kind: Type,
     pub optional: Option<Expr>,
     pub is_ref: bool,
+    pub is_variadic: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -35,6 +36,7 @@ impl FunctionDecl {
                 kind: arg.kind,
                 optional: arg.optional,
                 is_ref: arg.is_ref,
+                is_variadic: arg.is_variadic,
                 tok: None,
             })
             .collect();
@@ -132,10 +134,27 @@ impl VariableDecl {
     }
 }
 
+/// Local variables that an anonymous function copies when it is created
+#[derive(Clone, Debug)]
+pub struct LambdaCaptures {
+    /// Index of the scope that holds the copies
+    pub scope: usize,
+    /// Captured variables paired with their copies
+    pub vars: Vec<(VariableDecl, VariableDecl)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeDecl {
+    pub name: String,
+    pub kind: Type,
+    pub is_public: bool,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct ScopeUnit {
     pub vars: HashMap<String, VariableDecl>,
     pub funs: HashMap<String, FunctionDecl>,
+    pub types: HashMap<String, TypeDecl>,
 }
 
 /// Perform methods just on the scope
@@ -179,6 +198,19 @@ impl ScopeUnit {
     pub fn get_fun_names(&self) -> Vec<&String> {
         self.funs.keys().collect()
     }
+
+    /* Types */
+
+    /// Persists a type declaration in the scope
+    pub fn add_type(&mut self, decl: TypeDecl) -> bool {
+        let name = decl.name.clone();
+        self.types.insert(name, decl).is_none()
+    }
+
+    /// Fetches a type declaration from the scope
+    pub fn get_type(&self, name: &str) -> Option<&TypeDecl> {
+        self.types.get(name)
+    }
 }
 
 #[derive(Clone, Debug, ContextHelper)]
@@ -205,12 +237,32 @@ pub struct Context {
     pub is_trust_ctx: bool,
     /// Determines if the context is in a test block
     pub is_test_ctx: bool,
+    /// Determines if the context is in the body of a try block
+    #[context]
+    pub is_try_ctx: bool,
+    /// Determines if the context is in a body that runs as a background job
+    #[context]
+    pub is_job_ctx: bool,
+    /// Index of the first scope of the body that runs as a background job
+    #[context]
+    pub job_scope: Option<usize>,
+    /// Determines if the context is in a defer block
+    #[context]
+    pub is_defer_ctx: bool,
+    /// Determines if the currently parsed function or main block defers code
+    pub has_defers: bool,
     /// This is a list of ids of all the public functions in the file
     pub pub_funs: Vec<FunctionDecl>,
     /// This is a list of all the public variables in the file
     pub pub_vars: Vec<VariableDecl>,
+    /// This is a list of all the public types in the file
+    pub pub_types: Vec<TypeDecl>,
     /// The return type of the currently parsed function
     pub fun_ret_type: Option<Type>,
+    /// Captures of the anonymous functions that are currently parsed
+    pub lambda_captures: Vec<LambdaCaptures>,
+    /// Type parameters of the current function and the types they are bound to
+    pub type_params: HashMap<String, Type>,
     /// List of compiler flags
     #[context]
     pub cc_flags: HashSet<CCFlags>,
@@ -230,9 +282,17 @@ impl Context {
             is_main_ctx: false,
             is_trust_ctx: false,
             is_test_ctx: false,
+            is_try_ctx: false,
+            is_job_ctx: false,
+            job_scope: None,
+            is_defer_ctx: false,
+            has_defers: false,
             pub_funs: vec![],
             pub_vars: vec![],
+            pub_types: vec![],
             fun_ret_type: None,
+            lambda_captures: vec![],
+            type_params: HashMap::new(),
             cc_flags: HashSet::new(),
         }
     }
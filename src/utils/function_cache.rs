@@ -1,6 +1,6 @@
 use super::context::Context;
 use crate::modules::{block::Block, types::Type};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug)]
 /// This is a compiled function instance
@@ -23,6 +23,8 @@ pub struct FunctionCacheEntry {
     pub block: Block,
     /// Whether the first-pass typecheck with declared types has already been done
     pub first_pass_done: bool,
+    /// The variants that are used as function values
+    pub referenced: HashSet<usize>,
 }
 
 #[derive(Debug, Default)]
@@ -45,6 +47,7 @@ impl FunctionCache {
                 context,
                 block,
                 first_pass_done: false,
+                referenced: HashSet::new(),
             },
         );
     }
@@ -88,4 +91,18 @@ impl FunctionCache {
             entry.first_pass_done = true;
         }
     }
+
+    /// Marks a function variant as used as a function value
+    pub fn set_referenced(&mut self, id: usize, variant_id: usize) {
+        if let Some(entry) = self.funs.get_mut(&id) {
+            entry.referenced.insert(variant_id);
+        }
+    }
+
+    /// Checks if a function variant is used as a function value
+    pub fn is_referenced(&self, id: usize, variant_id: usize) -> bool {
+        self.funs
+            .get(&id)
+            .is_some_and(|f| f.referenced.contains(&variant_id))
+    }
 }
@@ -26,6 +26,7 @@ impl FunctionInterface {
                 kind: arg.kind,
                 optional: arg.optional,
                 is_ref: arg.is_ref,
+                is_variadic: arg.is_variadic,
             })
             .collect();
 
@@ -3,12 +3,17 @@ use crate::modules::types::Type;
 use crate::raw_fragment;
 use crate::utils::is_all_caps;
 
+/// Functions called through a function value store their result in this variable
+pub const FUNCTION_VALUE_RETURN: &str = "ret_fun_ref";
+
 #[derive(Clone)]
 pub struct FunctionMetadata {
     name: String,
     id: usize,
     variant: usize,
     returns: Type,
+    is_anonymous: bool,
+    has_defers: bool,
 }
 
 impl FunctionMetadata {
@@ -20,11 +25,37 @@ impl FunctionMetadata {
             id,
             variant,
             returns,
+            is_anonymous: false,
+            has_defers: false,
+        }
+    }
+
+    /// Anonymous functions can only be called through a function value
+    pub fn new_anonymous(returns: &Type) -> Self {
+        FunctionMetadata {
+            name: String::new(),
+            id: 0,
+            variant: 0,
+            returns: returns.clone(),
+            is_anonymous: true,
+            has_defers: false,
         }
     }
 
+    pub fn with_defers(mut self, has_defers: bool) -> Self {
+        self.has_defers = has_defers;
+        self
+    }
+
+    /// Whether the function runs deferred code before it returns
+    pub fn has_defers(&self) -> bool {
+        self.has_defers
+    }
+
     pub fn mangled_name(&self) -> String {
-        if is_all_caps(&self.name) {
+        if self.is_anonymous {
+            FUNCTION_VALUE_RETURN.to_string()
+        } else if is_all_caps(&self.name) {
             format!("__ret_{}{}_v{}", self.name, self.id, self.variant)
         } else {
             format!("ret_{}{}_v{}", self.name, self.id, self.variant)
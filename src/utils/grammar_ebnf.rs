@@ -11,17 +11,26 @@ root = { statement_global } ;
 (* Statement *)
 statement_local =
     builtins_statement |
+    defer |
+    job_kill |
+    on_signal |
     expression |
     if_chain |
     if_statement |
+    match |
+    try_block |
     loop |
     loop_array |
+    parallel_loop |
     while_loop |
     variable_init_const |
     variable_init_mut |
     variable_set ;
 statement_global =
     function_def |
+    struct_def |
+    enum_def |
+    type_def |
     import_all |
     import_ids |
     main |
@@ -38,20 +47,35 @@ expression =
     binary_operation |
     boolean |
     builtins_expression |
+    capture |
+    coalesce |
     command |
+    duration |
+    enum_variant |
     function_call |
     function_call_failed |
     identifier |
+    lambda |
     array |
+    map |
+    match_expression |
     null |
     number |
     parentheses |
     range |
     range_inclusive |
+    spawn |
+    struct_literal |
+    struct_field |
     ternary |
     text |
+    text_multiline |
+    text_raw |
+    tuple |
     unary_operation |
-    expression_index ;
+    expression_index |
+    expression_slice |
+    force ;
 
     (* Keywords - auto-generated at build time *)
 
@@ -60,9 +84,12 @@ ANY_CHAR = ? any character ? ;
 LETTER = 'A'..'Z' | 'a'..'z' ;
 DIGIT = '0'..'9' ;
 TYPE = SIMPLE_TYPE, { '|', SIMPLE_TYPE } ;
-SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | '[', TYPE, ']' ;
+TYPE_NAME = 'A'..'Z', { LETTER | DIGIT } ;
+SIMPLE_TYPE = 'Text' | 'Num' | 'Bool' | 'Null' | 'Int' | 'Job' | 'Duration' | TYPE_NAME | '[', TYPE, ']' | '[', 'Text', ':', TYPE, ']' | TUPLE_TYPE | FUNCTION_TYPE ;
+TUPLE_TYPE = '(', TYPE, ',', TYPE, { ',', TYPE }, ')' ;
+FUNCTION_TYPE = 'Fun', '(', [ TYPE, { ',', TYPE } ], ')', [ ':', SIMPLE_TYPE ] ;
 UNARY_OP = '-' | KEYWORD_NOT ;
-BINARY_OP = '+' | '-' | '*' | '/' | '%' | KEYWORD_AND | KEYWORD_OR | '==' | '!=' | '<' | '<=' | '>' | '>=' ;
+BINARY_OP = '+' | '-' | '*' | '/' | '%' | KEYWORD_AND | KEYWORD_OR | '==' | '!=' | '<' | '<=' | '>' | '>=' | KEYWORD_IN ;
 VISIBILITY = KEYWORD_PUB ;
 
 (* Identifier *)
@@ -75,9 +102,15 @@ integer = DIGIT, { DIGIT } ;
 real = integer, '.', integer ;
 number = integer | real ;
 
+(* `Duration` literal *)
+duration = integer, ( 'ms' | 's' | 'm' | 'h' ) ;
+
 (* `Text` literal *)
-interpolation = '{', expression, '}' ;
+interpolation = '{', expression, [ ':', format_spec ], '}' ;
+format_spec = [ '<' | '>' ], [ '0' ], [ integer ], [ '.', integer ], [ 'x' | 'X' | 'o' | 'e' | 'f' ] ;
 text = '"', { ANY_CHAR | interpolation }, '"' ;
+text_multiline = '"""', { ANY_CHAR | interpolation }, '"""' ;
+text_raw = 'r"', { ANY_CHAR }, '"' | 'r"""', { ANY_CHAR }, '"""' ;
 
 (* `Bool` literal *)
 boolean = 'true' | 'false' ;
@@ -90,24 +123,45 @@ empty_array = '[', [ TYPE ], ']' ;
 full_array = '[', [ expression, { ',', expression } ], ']' ;
 array = empty_array | full_array ;
 
+(* `Map` literal *)
+empty_map = '[', 'Text', ':', TYPE, ']' ;
+map_entry = expression, ':', expression ;
+full_map = '[', map_entry, { ',', map_entry }, [ ',' ], ']' ;
+map = empty_map | full_map ;
+
+(* `Tuple` literal *)
+tuple = '(', expression, ',', expression, { ',', expression }, ')' ;
+
 (* Command expression *)
 (* The ordering of command modifiers doesn't matter *)
 command_modifier = [ KEYWORD_SILENT | KEYWORD_SUPPRESS ], [ KEYWORD_TRUST ], [ KEYWORD_SUDO ] ;
-command_modifier_block = command_modifier, multiline_block ;
+command_timeout = KEYWORD_TIMEOUT, '(', expression, ')' ;
+command_modifier_block = command_modifier, [ command_timeout ], multiline_block, [ handler ] ;
 command_base = '$', { ANY_CHAR | interpolation }, '$' ;
-command = command_modifier, command_base, [ handler ] ;
+command_pipe = '|', ( command_base | identifier ) ;
+command_input = '<', expression ;
+command_output = ( '>' | '>>' | '2>' ), expression ;
+command = command_modifier, [ command_timeout ], command_base, [ command_input ], { command_pipe }, [ command_input ], { command_output }, [ handler ] ;
+capture = KEYWORD_CAPTURE, command ;
+spawn = KEYWORD_SPAWN, command ;
+job_kill = identifier, '.', 'kill', '(', ')' ;
 
 (* Operations *)
 binary_operation = expression, BINARY_OP, expression ;
 unary_operation = UNARY_OP, expression ;
 expression_index = expression, '[', expression, ']' ;
+expression_slice = expression, '[', expression, '..', ']' ;
+
+(* Null coalescing *)
+coalesce = expression, '??', expression ;
+force = expression, '!' ;
 
 (* Parentheses *)
 parentheses = '(', expression, ')' ;
 
 (* Failure handler *)
 failure_propagation = '?';
-failure_block = KEYWORD_FAILED, [ '(', identifier, ')' ], block ;
+failure_block = KEYWORD_FAILED, [ '(', identifier, [ ',', identifier ], ')' ], block ;
 failure_handler = failure_propagation | failure_block ;
 
 (* Success handler *)
@@ -115,12 +169,15 @@ success_block = KEYWORD_SUCCEEDED, block ;
 success_handler = success_block ;
 
 (* Exited handler *)
-exited_block = KEYWORD_EXITED, [ '(', identifier, ')' ], block ;
+exited_block = KEYWORD_EXITED, [ '(', identifier, [ ',', identifier ], ')' ], block ;
 exited_handler = exited_block ;
 
 (* All handlers combined *)
 handler = success_handler | failure_handler | exited_handler ;
 
+(* Try block *)
+try_block = KEYWORD_TRY, block, handler ;
+
 (* Attributes *)
 attribute_name = any_identifier ;
 attribute = '#[', attribute_name, ']' ;
@@ -133,17 +190,37 @@ variable_get = identifier ;
 variable_set = identifier, variable_index?, '=', expression ;
 
 (* Function *)
-function_call = command_modifier, identifier, '(', [ expression, { ',', expression } ], ')' ;
+function_arg = [ '...' ], expression | identifier, ':', expression ;
+function_call = command_modifier, identifier, '(', [ function_arg, { ',', function_arg } ], ')' ;
 function_call_failed = function_call, [ handler ] ;
-function_def = { attribute }, [ VISIBILITY ], KEYWORD_FUN, identifier, '(', [ identifier, { ',', identifier } ], ')', block ;
-function_def_typed = { attribute }, [ VISIBILITY ], KEYWORD_FUN, identifier, '(',
-    [ identifier, ':', TYPE, { ',', identifier, ':', TYPE } ], ')', ':', TYPE, block ;
+function_def = { attribute }, [ VISIBILITY ], KEYWORD_FUN, identifier, '(', [ function_param, { ',', function_param } ], ')', block ;
+function_param = [ '...' ], identifier ;
+type_params = '<', TYPE_NAME, { ',', TYPE_NAME }, '>' ;
+function_def_typed = { attribute }, [ VISIBILITY ], KEYWORD_FUN, identifier, [ type_params ], '(',
+    [ function_param, ':', TYPE, { ',', function_param, ':', TYPE } ], ')', ':', TYPE, block ;
+lambda = KEYWORD_FUN, '(', [ identifier, ':', TYPE, { ',', identifier, ':', TYPE } ], ')', [ ':', TYPE ], block ;
+
+(* Struct *)
+struct_field_def = identifier, ':', TYPE ;
+struct_def = [ VISIBILITY ], KEYWORD_STRUCT, TYPE_NAME, '{', struct_field_def, { [ ',' ], struct_field_def }, [ ',' ], '}' ;
+struct_literal = TYPE_NAME, '{', identifier, ':', expression, { [ ',' ], identifier, ':', expression }, [ ',' ], '}' ;
+struct_field = expression, '.', identifier ;
+
+(* Enum *)
+enum_def = [ VISIBILITY ], KEYWORD_ENUM, TYPE_NAME, '{', TYPE_NAME, { [ ',' ], TYPE_NAME }, [ ',' ], '}' ;
+enum_variant = TYPE_NAME, '.', TYPE_NAME ;
+
+(* Type alias *)
+type_alias_def = [ VISIBILITY ], KEYWORD_TYPE, TYPE_NAME, '=', TYPE ;
+type_distinct_def = [ VISIBILITY ], KEYWORD_TYPE, TYPE_NAME, TYPE ;
+type_def = type_alias_def | type_distinct_def ;
 
 (* Loop *)
 loop = KEYWORD_LOOP, block ;
 loop_array = KEYWORD_FOR | KEYWORD_LOOP, identifier, KEYWORD_IN, expression, block ;
 loop_array_iterator = KEYWORD_FOR | KEYWORD_LOOP, identifier, ',', identifier, KEYWORD_IN, expression, block ;
 while_loop = KEYWORD_WHILE, expression, block ;
+parallel_loop = KEYWORD_PARALLEL, '(', expression, ')', ( loop_array | loop_array_iterator ), handler ;
 
 (* Ranges *)
 range = expression, '..', expression ;
@@ -152,8 +229,18 @@ range_inclusive = expression, '..=', expression ;
 (* Conditional *)
 if_statement = KEYWORD_IF, expression, block, [ KEYWORD_ELSE, block ] ;
 if_chain = KEYWORD_IF, '{', { expression, block }, [ KEYWORD_ELSE, block ],  '}' ;
+match_case = expression | ( KEYWORD_IS, TYPE ) ;
+match_cases = match_case, { ',', match_case } ;
+match = KEYWORD_MATCH, expression, '{', { match_cases, block }, [ KEYWORD_ELSE, block ], '}' ;
+match_expression = KEYWORD_MATCH, expression, '{', { match_cases, ':', expression, [ ',' ] }, [ KEYWORD_ELSE, ':', expression ], '}' ;
 ternary = expression, KEYWORD_THEN, expression, KEYWORD_ELSE, expression ;
 
+(* Defer *)
+defer = KEYWORD_DEFER, block ;
+
+(* Signal handler *)
+on_signal = KEYWORD_ON, 'signal', '(', text, { ',', text }, ')', block ;
+
 (* Main *)
 main = KEYWORD_MAIN, [ '(', identifier, ')' ], block ;
 
@@ -205,7 +292,7 @@ pub fn generate_grammar_ebnf() -> String {
         let kw_upper = kw.to_uppercase();
         let builtin_name = format!("builtin_{}", kw);
         match *kw {
-            "clear" => {
+            "clear" | "await_all" => {
                 builtin_stmt_rules.push_str(&format!("{} = KEYWORD_{} ;\n", builtin_name, kw_upper))
             }
             _ => builtin_stmt_rules.push_str(&format!(
@@ -1,4 +1,4 @@
-use super::context::FunctionDecl;
+use super::context::{FunctionDecl, TypeDecl};
 use crate::{modules::block::Block, utils::context::VariableDecl};
 
 #[derive(Debug, Clone)]
@@ -6,6 +6,7 @@ pub struct FileMetadata {
     pub block: Block,
     pub pub_funs: Vec<FunctionDecl>,
     pub pub_vars: Vec<VariableDecl>,
+    pub pub_types: Vec<TypeDecl>,
 }
 
 #[derive(Debug, Clone)]
@@ -103,24 +104,29 @@ impl ImportCache {
         block: Block,
         pub_funs: Vec<FunctionDecl>,
         pub_vars: Vec<VariableDecl>,
+        pub_types: Vec<TypeDecl>,
     ) {
         let path_id = self.get_path_id(&Self::get_path(path)).unwrap();
         self.files[path_id].metadata = Some(FileMetadata {
             block,
             pub_funs,
             pub_vars,
+            pub_types,
         });
     }
 
     pub fn get_imports(
         &mut self,
         path: Option<String>,
-    ) -> Option<(Vec<FunctionDecl>, Vec<VariableDecl>)> {
+    ) -> Option<(Vec<FunctionDecl>, Vec<VariableDecl>, Vec<TypeDecl>)> {
         self.get_path_id(&Self::get_path(path)).and_then(|path_id| {
-            self.files[path_id]
-                .metadata
-                .as_ref()
-                .map(|meta| (meta.pub_funs.clone(), meta.pub_vars.clone()))
+            self.files[path_id].metadata.as_ref().map(|meta| {
+                (
+                    meta.pub_funs.clone(),
+                    meta.pub_vars.clone(),
+                    meta.pub_types.clone(),
+                )
+            })
         })
     }
 
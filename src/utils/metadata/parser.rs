@@ -2,7 +2,7 @@ use std::collections::{BTreeSet, HashMap};
 
 use crate::modules::block::Block;
 use crate::modules::types::Type;
-use crate::utils::context::{Context, FunctionDecl, ScopeUnit, VariableDecl};
+use crate::utils::context::{Context, FunctionDecl, ScopeUnit, TypeDecl, VariableDecl};
 use crate::utils::function_cache::FunctionCache;
 use crate::utils::function_interface::FunctionInterface;
 use crate::utils::import_cache::ImportCache;
@@ -50,6 +50,10 @@ pub struct ParserMetadata {
     pub shellname_used: bool,
     /// Whether shellversion() builtin is used anywhere in the code
     pub shellversion_used: bool,
+    /// Whether values packed into a single string (such as structs) are used anywhere in the code
+    pub packed_used: bool,
+    /// Position of the first map used in the code (maps are not supported by every shell)
+    pub map_used: Option<PositionInfo>,
 }
 
 impl ParserMetadata {
@@ -134,6 +138,18 @@ impl ParserMetadata {
             .find_map(|scope| scope.get_var(name))
     }
 
+    /// Checks if the variable is declared outside of the body that runs as a background job
+    pub fn is_var_outside_job(&self, name: &str) -> bool {
+        let Some(job_scope) = self.context.job_scope else {
+            return false;
+        };
+        self.context
+            .scopes
+            .iter()
+            .rposition(|scope| scope.get_var(name).is_some())
+            .is_some_and(|scope| scope < job_scope)
+    }
+
     /// Gets a variable from the current scope
     pub fn get_var_in_current_scope(&self, name: &str) -> Option<&VariableDecl> {
         self.context
@@ -154,10 +170,39 @@ impl ParserMetadata {
 
     /// Returns a variable and marks it as used
     pub fn get_var_used(&mut self, name: &str) -> Option<&VariableDecl> {
+        self.capture_var(name);
         self.mark_var_used(name);
         self.get_var(name)
     }
 
+    /// Copies a local variable of the enclosing scopes into the anonymous functions that use it
+    fn capture_var(&mut self, name: &str) {
+        for index in 0..self.context.lambda_captures.len() {
+            let capture_scope = self.context.lambda_captures[index].scope;
+            let found = self
+                .context
+                .scopes
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(scope, unit)| unit.get_var(name).map(|var| (scope, var.clone())));
+            let Some((scope, var)) = found else {
+                return;
+            };
+            // Global variables are accessible from every function
+            if scope == 0 || scope >= capture_scope {
+                continue;
+            }
+            let mut copy = VariableDecl::new(var.name.clone(), var.kind.clone()).with_const(true);
+            copy.global_id = Some(self.gen_var_id());
+            self.context.scopes[capture_scope].add_var(copy.clone());
+            if let Some(original) = self.context.scopes[scope].vars.get_mut(name) {
+                original.is_used = true;
+            }
+            self.context.lambda_captures[index].vars.push((var, copy));
+        }
+    }
+
     /// Marks a variable as used
     fn mark_var_used(&mut self, name: &str) {
         for scope in self.context.scopes.iter_mut().rev() {
@@ -280,6 +325,32 @@ impl ParserMetadata {
             .collect()
     }
 
+    /* Types */
+
+    /// Marks that maps are used in the code. Maps are passed around as packed values.
+    pub fn use_map(&mut self, position: PositionInfo) {
+        self.packed_used = true;
+        self.map_used.get_or_insert(position);
+    }
+
+    /// Adds a type declaration to the current scope
+    pub fn add_type_declaration(&mut self, decl: TypeDecl) -> bool {
+        if decl.is_public {
+            self.context.pub_types.push(decl.clone());
+        }
+        let scope = self.context.scopes.last_mut().unwrap();
+        scope.add_type(decl)
+    }
+
+    /// Gets a type declaration from the current scope or any parent scope
+    pub fn get_type_declaration(&self, name: &str) -> Option<&TypeDecl> {
+        self.context
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get_type(name))
+    }
+
     pub fn get_narrowed_type(&self, name: &str) -> Option<&Type> {
         self.narrowed_types
             .iter()
@@ -321,6 +392,8 @@ impl Metadata for ParserMetadata {
             sudo_used: false,
             shellname_used: false,
             shellversion_used: false,
+            packed_used: false,
+            map_used: None,
             first_pass_ctx: false,
         }
     }
@@ -74,6 +74,17 @@ impl ShellType {
     }
 }
 
+/// Shell loop that encloses the translated code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellLoop {
+    /// Loop statement of the source code
+    Loop,
+    /// Loop that runs the body of a try block once
+    Try,
+    /// Subshell that runs the body of a parallel loop or a block with a timeout as a background job
+    Job,
+}
+
 pub struct TargetShell {
     pub shell: ShellType,
 }
@@ -95,6 +106,8 @@ pub struct TranslateMetadata {
     pub value_id: usize,
     /// Determines whether the current context is a context in bash's `eval`.
     pub eval_ctx: bool,
+    /// Shell loops that enclose the translated code, the innermost one is last.
+    pub loops: Vec<ShellLoop>,
     /// Determines whether the current context should be silenced.
     #[context]
     pub silenced: bool,
@@ -130,6 +143,7 @@ impl TranslateMetadata {
             stmt_queue: VecDeque::new(),
             value_id: 0,
             eval_ctx: false,
+            loops: vec![],
             silenced: false,
             suppress: false,
             sudoed: false,
@@ -173,6 +187,23 @@ impl TranslateMetadata {
         id
     }
 
+    /// Translates the body of a shell loop of given kind
+    pub fn with_loop<T>(&mut self, kind: ShellLoop, body: impl FnOnce(&mut Self) -> T) -> T {
+        self.loops.push(kind);
+        let result = body(self);
+        self.loops.pop();
+        result
+    }
+
+    /// Returns the argument of `break` or `continue` that leaves the innermost loop of given kind
+    pub fn loop_depth(&self, kind: ShellLoop) -> Option<usize> {
+        self.loops
+            .iter()
+            .rev()
+            .position(|shell_loop| *shell_loop == kind)
+            .map(|position| position + 1)
+    }
+
     pub fn gen_silent(&self) -> FragmentKind {
         if self.silenced {
             raw_fragment!(">/dev/null 2>&1")